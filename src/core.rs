@@ -1,7 +1,22 @@
 // core.rs
 pub mod live_client;
+pub mod live_client_barrage;
+pub mod live_client_battle;
 pub mod live_client_builder;
+pub mod live_client_caption;
+pub mod live_client_chat;
+pub mod live_client_chat_history;
 pub mod live_client_events;
+pub mod live_client_goal;
+pub mod live_client_guest;
 pub mod live_client_http;
 pub mod live_client_mapper;
+pub mod live_client_pin;
+pub mod live_client_poll;
+pub mod live_client_polling;
+pub mod live_client_question;
+pub mod live_client_rank;
+pub mod live_client_shopping;
+pub mod live_client_text;
+pub mod live_client_treasure;
 pub mod live_client_websocket;
@@ -2,21 +2,51 @@
 use std::sync::Arc;
 use log::{error, info, warn};
 
+use crate::core::live_client_battle::{TikTokLiveBattle, TikTokLiveBattleTracker};
+use crate::core::live_client_caption::TikTokLiveCaptionAssembler;
+use crate::core::live_client_chat_history::TikTokLiveChatHistory;
 use crate::core::live_client_events::{TikTokLiveEvent, TikTokLiveEventObserver};
+use crate::core::live_client_goal::{TikTokLiveGoal, TikTokLiveGoalTracker};
+use crate::core::live_client_guest::{TikTokLiveGuest, TikTokLiveGuestTracker};
 use crate::core::live_client_http::TikTokLiveHttpClient;
-use crate::core::live_client_websocket::TikTokLiveWebsocketClient;
+use crate::core::live_client_pin::{TikTokLivePin, TikTokLivePinTracker};
+use crate::core::live_client_poll::{TikTokLivePoll, TikTokLivePollTracker};
+use crate::core::live_client_polling::TikTokLivePollingClient;
+use crate::core::live_client_question::{TikTokLiveQuestion, TikTokLiveQuestionQueue};
+use crate::core::live_client_rank::{TikTokLiveRank, TikTokLiveRankTracker};
+use crate::core::live_client_shopping::{TikTokLiveProduct, TikTokLiveShoppingTracker};
+use crate::core::live_client_websocket::{TikTokLiveWebsocketClient, TikTokWebSocketStream};
 use crate::data::live_common::ConnectionState::{self, CONNECTING, DISCONNECTED};
-use crate::data::live_common::{TikTokLiveInfo, TikTokLiveSettings};
+use crate::data::live_common::{
+    ProxyRotation, SessionUsage, TikTokLiveInfo, TikTokLiveSettings, TransportMode,
+};
+use crate::data::live_target::{normalize_user_name, validate_room_id};
 use crate::errors::LibError;
+use crate::generated::WebcastChatMessage;
 use crate::http::http_data::LiveStatus::HostOnline;
-use crate::http::http_data::{LiveConnectionDataRequest, LiveDataRequest, LiveUserDataRequest};
+use crate::http::http_proxy::proxy_url;
+use crate::http::http_data::{
+    LiveConnectionDataRequest, LiveConnectionDataResponse, LiveDataRequest, LiveDataResponse,
+    LiveUserDataRequest,
+};
 
 pub struct TikTokLiveClient {
     pub settings: TikTokLiveSettings,
-    http_client: TikTokLiveHttpClient,
+    pub(crate) http_client: TikTokLiveHttpClient,
     event_observer: TikTokLiveEventObserver,
     websocket_client: Arc<TikTokLiveWebsocketClient>,
+    pub(crate) polling_client: TikTokLivePollingClient,
     room_info: TikTokLiveInfo,
+    pub(crate) battle_tracker: TikTokLiveBattleTracker,
+    pub(crate) poll_tracker: TikTokLivePollTracker,
+    pub(crate) goal_tracker: TikTokLiveGoalTracker,
+    pub(crate) caption_assembler: TikTokLiveCaptionAssembler,
+    pub(crate) question_queue: TikTokLiveQuestionQueue,
+    pub(crate) rank_tracker: TikTokLiveRankTracker,
+    pub(crate) chat_history: TikTokLiveChatHistory,
+    pub(crate) pin_tracker: TikTokLivePinTracker,
+    pub(crate) shopping_tracker: TikTokLiveShoppingTracker,
+    pub(crate) guest_tracker: TikTokLiveGuestTracker,
 }
 
 impl TikTokLiveClient {
@@ -25,51 +55,229 @@ impl TikTokLiveClient {
         http_client: TikTokLiveHttpClient,
         event_observer: TikTokLiveEventObserver,
         websocket_client: TikTokLiveWebsocketClient,
+        polling_client: TikTokLivePollingClient,
         room_info: TikTokLiveInfo,
     ) -> Self {
+        let caption_assembler =
+            TikTokLiveCaptionAssembler::new(settings.caption_export.clone(), settings.display_name());
+        let chat_history = TikTokLiveChatHistory::new(settings.chat_history_size);
+        let shopping_tracker = TikTokLiveShoppingTracker::new(settings.product_history_size);
         TikTokLiveClient {
             settings,
             http_client,
             event_observer,
             websocket_client: Arc::new(websocket_client),
+            polling_client,
             room_info,
+            battle_tracker: TikTokLiveBattleTracker::default(),
+            poll_tracker: TikTokLivePollTracker::default(),
+            goal_tracker: TikTokLiveGoalTracker::default(),
+            caption_assembler,
+            question_queue: TikTokLiveQuestionQueue::default(),
+            rank_tracker: TikTokLiveRankTracker::default(),
+            chat_history,
+            pin_tracker: TikTokLivePinTracker::default(),
+            shopping_tracker,
+            guest_tracker: TikTokLiveGuestTracker::default(),
         }
     }
 
-    pub async fn connect(mut self) -> Result<(), LibError> {
-        if *self.room_info.connection_state.lock().unwrap() != DISCONNECTED {
-            warn!("Client is already connected or connecting.");
+    async fn fetch_room(&mut self) -> Result<(String, LiveDataResponse), LibError> {
+        let room_id = match &self.settings.room_id {
+            Some(room_id) => room_id.clone(),
+            None => {
+                if let Some(link) = self.settings.share_link.clone() {
+                    if self.settings.host_name.is_empty() {
+                        info!("Resolving share link '{}'...", link);
+                        self.settings.host_name = self.http_client.resolve_share_link(&link).await?;
+                        self.caption_assembler.set_file_prefix(&self.settings.host_name);
+                    }
+                }
+                info!("Fetching room ID for user '{}'...", &self.settings.host_name);
+                let user_data = self.http_client.resolve_live_user_data(LiveUserDataRequest {
+                    user_name: self.settings.host_name.clone(),
+                }).await?;
+                user_data.room_id
+            }
+        };
+
+        info!("Fetching room info for room ID '{}'...", &room_id);
+        let room_data = self.http_client.fetch_live_data(LiveDataRequest {
+            room_id: room_id.clone(),
+        }).await?;
+        Ok((room_id, room_data))
+    }
+
+    /// Checks the room id, or normalizes the username unless it still has to come from a
+    /// share link.
+    fn validate_target(&mut self) -> Result<(), LibError> {
+        if let Some(room_id) = &self.settings.room_id {
+            return validate_room_id(room_id);
+        }
+        if self.settings.share_link.is_some() && self.settings.host_name.is_empty() {
             return Ok(());
         }
-        self.set_connection_state(CONNECTING);
+        let user_name = normalize_user_name(&self.settings.host_name)?;
+        if user_name != self.settings.host_name {
+            self.caption_assembler.set_file_prefix(&user_name);
+            self.settings.host_name = user_name;
+        }
+        Ok(())
+    }
 
-        info!("Fetching room ID for user '{}'...", &self.settings.host_name);
-        let user_data = self.http_client.fetch_live_user_data(LiveUserDataRequest {
-            user_name: self.settings.host_name.clone(),
-        }).await?;
+    fn validate_session(&self) -> Result<(), LibError> {
+        let Some(session) = &self.settings.session else {
+            return Ok(());
+        };
+        if session.session_id.is_empty() {
+            return Err(LibError::InvalidSession("session_id is empty".to_string()));
+        }
+        if !session.session_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(LibError::InvalidSession(
+                "session_id must be the bare sessionid cookie value".to_string(),
+            ));
+        }
+        let idc_is_valid = session
+            .tt_target_idc
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !idc_is_valid {
+            return Err(LibError::InvalidSession(
+                "tt_target_idc must be the bare tt-target-idc cookie value".to_string(),
+            ));
+        }
+        Ok(())
+    }
 
-        info!("Fetching room info for room ID '{}'...", &user_data.room_id);
-        let room_data = self.http_client.fetch_live_data(LiveDataRequest {
-            room_id: user_data.room_id.clone(),
-        }).await?;
+    /// Checks the configured proxy and every proxy of the pool, so a typo fails `connect`
+    /// instead of sending requests without the proxy.
+    fn validate_proxies(&self) -> Result<(), LibError> {
+        let pool_proxies = self.settings.proxy_pool.iter().flat_map(|pool| &pool.proxies);
+        for proxy in self.settings.http_data.proxy.iter().chain(pool_proxies) {
+            proxy_url(proxy)?;
+        }
+        Ok(())
+    }
+
+    async fn fetch_connection_data(&mut self) -> Result<LiveConnectionDataResponse, LibError> {
+        let (room_id, room_data) = match self.fetch_room().await {
+            Err(e) if e.needs_session() && self.http_client.authenticate() => {
+                warn!("Anonymous access failed ({}), retrying with the session...", e);
+                self.fetch_room().await?
+            }
+            result => result?,
+        };
 
         self.room_info.client_data = room_data.json;
         if room_data.live_status != HostOnline {
-            error!("Host '{}' is not online. Status: {:?}", &self.settings.host_name, room_data.live_status);
+            error!("Host '{}' is not online. Status: {:?}", self.settings.display_name(), room_data.live_status);
             self.set_connection_state(DISCONNECTED);
             return Err(LibError::HostNotOnline);
         }
 
         info!("Fetching websocket connection details...");
-        let connection_data = self.http_client.fetch_live_connection_data(LiveConnectionDataRequest {
-            room_id: user_data.room_id.clone(),
-        }).await?;
+        self.http_client.fetch_live_connection_data(LiveConnectionDataRequest {
+            room_id,
+        }).await
+    }
+
+    /// Whether a failed connection may move on to another proxy of an `OnFailure` pool.
+    fn can_rotate_proxy(&self, attempt: usize) -> bool {
+        self.settings
+            .proxy_pool
+            .as_ref()
+            .filter(|pool| pool.rotation == ProxyRotation::OnFailure)
+            .is_some_and(|pool| attempt + 1 < pool.proxies.len())
+    }
+
+    /// Moves to the next proxy of an `OnFailure` pool, `false` once every proxy was tried.
+    fn rotate_proxy(&mut self, attempt: usize) -> bool {
+        if !self.can_rotate_proxy(attempt) {
+            return false;
+        }
+        let proxy = self.settings.proxy_pool.as_ref().and_then(|pool| pool.next_proxy());
+        self.http_client.use_proxy(proxy);
+        true
+    }
+
+    /// Opens the websocket unless polling is configured, `None` means polling is used.
+    ///
+    /// In `Auto` mode a websocket that cannot be opened falls back to polling, except for a
+    /// proxy failure that can still be retried with the next proxy.
+    async fn open_websocket(
+        &self,
+        connection_data: &LiveConnectionDataResponse,
+        attempt: usize,
+    ) -> Result<Option<TikTokWebSocketStream>, LibError> {
+        match self.settings.transport {
+            TransportMode::Polling => Ok(None),
+            TransportMode::WebSocket => self.websocket_client.connect(connection_data).await.map(Some),
+            TransportMode::Auto => match self.websocket_client.connect(connection_data).await {
+                Ok(stream) => Ok(Some(stream)),
+                Err(e) if is_proxy_failure(&e) && self.can_rotate_proxy(attempt) => Err(e),
+                Err(e) => {
+                    warn!("Websocket failed ({}), falling back to polling...", e);
+                    Ok(None)
+                }
+            },
+        }
+    }
+
+    pub async fn connect(mut self) -> Result<(), LibError> {
+        if *self.room_info.connection_state.lock().unwrap() != DISCONNECTED {
+            warn!("Client is already connected or connecting.");
+            return Ok(());
+        }
+        self.set_connection_state(CONNECTING);
+
+        let validation = self
+            .validate_target()
+            .and_then(|_| self.validate_session())
+            .and_then(|_| self.validate_proxies());
+        if let Err(e) = validation {
+            self.set_connection_state(DISCONNECTED);
+            return Err(e);
+        }
+        let session_usage = self.settings.session.as_ref().map(|session| session.usage);
+        if session_usage == Some(SessionUsage::Always) {
+            self.http_client.authenticate();
+        }
+
+        // A dead proxy may fail the HTTP requests or only the websocket tunnel, both rotate.
+        let mut attempt = 0;
+        let (connection_data, ws_stream) = loop {
+            let result = match self.fetch_connection_data().await {
+                Ok(connection_data) => self
+                    .open_websocket(&connection_data, attempt)
+                    .await
+                    .map(|ws_stream| (connection_data, ws_stream)),
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(e) if is_proxy_failure(&e) && self.rotate_proxy(attempt) => {
+                    warn!("Connecting failed ({}), retrying with the next proxy...", e);
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.set_connection_state(DISCONNECTED);
+                    return Err(e);
+                }
+                Ok(result) => break result,
+            }
+        };
 
         // The client needs to be heap-allocated to be shared across threads.
         let client_arc = Arc::new(self);
         
-        // Start the websocket client. It will manage its own lifecycle in spawned tasks.
-        client_arc.websocket_client.start(connection_data, client_arc.clone()).await?;
+        // Start the websocket or polling client. It will manage its own lifecycle in spawned tasks.
+        match ws_stream {
+            Some(ws_stream) => {
+                client_arc.websocket_client.start(ws_stream, &connection_data, client_arc.clone()).await?;
+            }
+            None => {
+                client_arc.polling_client.start(&connection_data, client_arc.clone()).await?;
+            }
+        }
 
         Ok(())
     }
@@ -77,6 +285,7 @@ impl TikTokLiveClient {
     pub fn disconnect(&self) {
         info!("Disconnect requested by user.");
         self.websocket_client.stop();
+        self.polling_client.stop();
         // The connection state will be set to DISCONNECTED by the websocket task itself upon exit.
     }
 
@@ -84,13 +293,108 @@ impl TikTokLiveClient {
         self.event_observer.publish(self, event);
     }
 
+    /// Publishes an event decoded from a message, flagged as history when the message is.
+    pub(crate) fn publish_message_event(&self, event: TikTokLiveEvent, is_history: bool) {
+        self.event_observer.publish_with_history(self, event, is_history);
+    }
+
+    /// Publishes caption sentences that never got their final fragment.
+    pub(crate) fn flush_captions(&self) {
+        for event in self.caption_assembler.flush() {
+            self.publish_event(event);
+        }
+    }
+
     pub fn get_room_info(&self) -> &String {
         &self.room_info.client_data
     }
 
+    /// Returns the current state of a link-mic battle seen in this room.
+    pub fn get_battle(&self, battle_id: i64) -> Option<TikTokLiveBattle> {
+        self.battle_tracker.get_battle(battle_id)
+    }
+
+    /// Returns all link-mic battles seen in this room, finished ones included.
+    pub fn get_battles(&self) -> Vec<TikTokLiveBattle> {
+        self.battle_tracker.get_battles()
+    }
+
+    /// Returns the current (or last finished) poll in the room.
+    pub fn get_poll(&self) -> Option<TikTokLivePoll> {
+        self.poll_tracker.get_poll()
+    }
+
+    /// Returns the current progress of every active LIVE goal in the room.
+    pub fn get_goals(&self) -> Vec<TikTokLiveGoal> {
+        self.goal_tracker.get_active_goals()
+    }
+
+    /// Returns every Q&A question asked in the room, oldest first.
+    pub fn get_questions(&self) -> Vec<TikTokLiveQuestion> {
+        self.question_queue.get_questions()
+    }
+
+    /// Returns the Q&A questions that have not been answered yet, oldest first.
+    pub fn get_pending_questions(&self) -> Vec<TikTokLiveQuestion> {
+        self.question_queue.get_pending_questions()
+    }
+
+    /// Marks a Q&A question as answered, e.g. from a moderator dashboard.
+    pub fn mark_question_answered(&self, question_id: i64) -> bool {
+        self.question_queue.mark_answered(question_id)
+    }
+
+    /// Returns the host's current place on the given leaderboard, e.g. `hourly_rank`.
+    pub fn get_rank(&self, list: &str) -> Option<TikTokLiveRank> {
+        self.rank_tracker.get_rank(list)
+    }
+
+    /// Returns the host's current place on every leaderboard seen so far.
+    pub fn get_ranks(&self) -> Vec<TikTokLiveRank> {
+        self.rank_tracker.get_ranks()
+    }
+
+    /// Returns the latest chat messages, oldest first, without deleted or banned content.
+    pub fn get_chat_history(&self) -> Vec<WebcastChatMessage> {
+        self.chat_history.get_messages()
+    }
+
+    /// Returns the item currently pinned in the room.
+    pub fn get_pinned_message(&self) -> Option<TikTokLivePin> {
+        self.pin_tracker.get_pin()
+    }
+
+    /// Returns the product currently featured in a LIVE shopping stream.
+    pub fn get_featured_product(&self) -> Option<TikTokLiveProduct> {
+        self.shopping_tracker.get_featured_product()
+    }
+
+    /// Returns every product showcased so far, with the time it was shown.
+    pub fn get_product_history(&self) -> Vec<TikTokLiveProduct> {
+        self.shopping_tracker.get_product_history()
+    }
+
+    /// Returns the guests and co-hosts currently on a mic seat, ordered by position.
+    pub fn get_guests(&self) -> Vec<TikTokLiveGuest> {
+        self.guest_tracker.get_guests()
+    }
+
+    /// Returns the host's current LIVE intro text, empty until TikTok sends one.
+    pub fn get_intro(&self) -> String {
+        self.room_info.intro.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set_intro(&self, intro: &str) {
+        *self.room_info.intro.lock().unwrap() = intro.to_string();
+    }
+
     pub fn set_connection_state(&self, state: ConnectionState) {
         let mut data = self.room_info.connection_state.lock().unwrap();
         *data = state;
         info!("Connection state changed to: {:?}", *data);
     }
-}
\ No newline at end of file
+}
+
+fn is_proxy_failure(error: &LibError) -> bool {
+    matches!(error, LibError::ProxyConnectFailed(_))
+}
@@ -0,0 +1,107 @@
+// live_client_barrage.rs
+use crate::generated::text::text_piece::TextPieceType;
+use crate::generated::webcast_barrage_message::BarrageType;
+use crate::generated::{User, WebcastBarrageMessage};
+
+/// Sub-type specific data of a barrage banner.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BarrageDetails {
+    /// A user reached a new gifter level, or entered the room with a high one.
+    UserGrade {
+        grade: i32,
+    },
+    /// A fan club member levelled up, or entered the room.
+    FansLevel {
+        level: i32,
+    },
+    SubscribeGift {
+        gift_sub_count: i64,
+    },
+    GiftGallery {
+        from_user_id: i64,
+        to_user_id: i64,
+    },
+    None,
+}
+
+/// A big on-screen banner (VIP entrance, level-up, gift gallery, ...) from `WebcastBarrageMessage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLiveBarrage {
+    pub kind: BarrageType,
+    pub user: Option<User>,
+    pub display_text: String,
+    pub details: BarrageDetails,
+}
+
+impl TikTokLiveBarrage {
+    pub fn from_message(message: &WebcastBarrageMessage) -> Self {
+        let kind = message
+            .msg_type
+            .and_then(|msg_type| BarrageType::try_from(msg_type).ok())
+            .unwrap_or(BarrageType::Unknown);
+        let content = message
+            .content
+            .as_ref()
+            .or(message.common_barrage_content.as_ref());
+        let user = message
+            .user_grade_param
+            .as_ref()
+            .and_then(|param| param.user.clone())
+            .or_else(|| {
+                message
+                    .fans_level_param
+                    .as_ref()
+                    .and_then(|param| param.user.clone())
+            })
+            .or_else(|| {
+                content?
+                    .pieces_list
+                    .iter()
+                    .find_map(|piece| match &piece.text_piece_type {
+                        Some(TextPieceType::UserValue(value)) => value.user.clone(),
+                        _ => None,
+                    })
+            });
+
+        TikTokLiveBarrage {
+            kind,
+            user,
+            display_text: content
+                .map(|text| text.render())
+                .unwrap_or_default(),
+            details: map_details(kind, message),
+        }
+    }
+}
+
+fn map_details(kind: BarrageType, message: &WebcastBarrageMessage) -> BarrageDetails {
+    let details = match kind {
+        BarrageType::UserUpgrade | BarrageType::GradeUserEntranceNotification => {
+            let param = message.user_grade_param.as_ref();
+            param.map(|param| BarrageDetails::UserGrade {
+                grade: param.current_grade,
+            })
+        }
+        BarrageType::FansLevelUpgrade | BarrageType::FansLevelEntrance => {
+            let param = message.fans_level_param.as_ref();
+            param.map(|param| BarrageDetails::FansLevel {
+                level: param.current_grade,
+            })
+        }
+        BarrageType::SubscribeGift => {
+            let param = message.subscribe_gift_param.as_ref();
+            param.map(|param| BarrageDetails::SubscribeGift {
+                gift_sub_count: param.gift_sub_count,
+            })
+        }
+        BarrageType::GiftGallery => {
+            let param = message.gift_gallery_params.as_ref();
+            param.map(|param| BarrageDetails::GiftGallery {
+                from_user_id: param.from_user_id,
+                to_user_id: param.to_user_id,
+            })
+        }
+        _ => None,
+    };
+    details.unwrap_or(BarrageDetails::None)
+}
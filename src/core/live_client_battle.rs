@@ -0,0 +1,199 @@
+// live_client_battle.rs
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::{
+    BattleAction, BattleSetting, BattleUserArmies, Result as BattleOutcome, WebcastLinkMicArmies,
+    WebcastLinkMicBattle, WebcastLinkMicBattlePunishFinish,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BattleStatus {
+    InProgress,
+    Finished,
+    PunishFinished,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BattleParticipant {
+    pub user_id: i64,
+    pub nickname: String,
+    pub display_id: String,
+    pub team_id: Option<i64>,
+}
+
+/// Snapshot of a single link-mic (PK) battle, as seen by the client so far.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLiveBattle {
+    pub battle_id: i64,
+    pub status: BattleStatus,
+    pub participants: Vec<BattleParticipant>,
+    /// Host score per participant `user_id`.
+    pub scores: HashMap<i64, i64>,
+    pub start_time_ms: i64,
+    pub end_time_ms: i64,
+    pub duration: Duration,
+    /// `user_id` of the winner, `None` while running or when the battle ended in a draw.
+    pub winner: Option<i64>,
+    /// Set once `OnBattleStarted` was published, armies may create the battle before that.
+    started_emitted: bool,
+}
+
+impl TikTokLiveBattle {
+    fn new(battle_id: i64) -> Self {
+        TikTokLiveBattle {
+            battle_id,
+            status: BattleStatus::InProgress,
+            participants: vec![],
+            scores: HashMap::new(),
+            start_time_ms: 0,
+            end_time_ms: 0,
+            duration: Duration::ZERO,
+            winner: None,
+            started_emitted: false,
+        }
+    }
+
+    /// Time left until the battle ends, or zero once it is over.
+    pub fn time_remaining(&self) -> Duration {
+        if self.status != BattleStatus::InProgress {
+            return Duration::ZERO;
+        }
+        let end_time_ms = if self.end_time_ms > 0 {
+            self.end_time_ms
+        } else {
+            self.start_time_ms + self.duration.as_millis() as i64
+        };
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+        Duration::from_millis((end_time_ms - now_ms).max(0) as u64)
+    }
+
+    fn apply_setting(&mut self, setting: &BattleSetting) {
+        if setting.start_time_ms > 0 {
+            self.start_time_ms = setting.start_time_ms;
+        }
+        if setting.end_time_ms > 0 {
+            self.end_time_ms = setting.end_time_ms;
+        }
+        if setting.duration > 0 {
+            self.duration = Duration::from_secs(setting.duration as u64);
+        }
+    }
+
+    fn apply_armies(&mut self, armies: &HashMap<i64, BattleUserArmies>) {
+        for (user_id, army) in armies {
+            self.scores.insert(*user_id, army.host_score);
+        }
+    }
+}
+
+/// Keeps per-battle state built from `WebcastLinkMicBattle` and `WebcastLinkMicArmies` messages.
+#[derive(Default)]
+pub struct TikTokLiveBattleTracker {
+    battles: Mutex<HashMap<i64, TikTokLiveBattle>>,
+}
+
+impl TikTokLiveBattleTracker {
+    pub fn get_battle(&self, battle_id: i64) -> Option<TikTokLiveBattle> {
+        self.battles.lock().unwrap().get(&battle_id).cloned()
+    }
+
+    pub fn get_battles(&self) -> Vec<TikTokLiveBattle> {
+        self.battles.lock().unwrap().values().cloned().collect()
+    }
+
+    pub(crate) fn handle_battle(&self, message: &WebcastLinkMicBattle) -> Option<TikTokLiveEvent> {
+        let mut battles = self.battles.lock().unwrap();
+        let battle = battles
+            .entry(message.battle_id)
+            .or_insert_with(|| TikTokLiveBattle::new(message.battle_id));
+
+        if let Some(setting) = &message.battle_setting {
+            battle.apply_setting(setting);
+        }
+        if !message.anchor_info.is_empty() {
+            battle.participants = map_participants(message);
+        }
+        battle.apply_armies(&message.armies);
+
+        let action = BattleAction::try_from(message.action).unwrap_or(BattleAction::Unknown);
+        let finished = !message.battle_result.is_empty()
+            || matches!(action, BattleAction::Finish | BattleAction::CutShort);
+
+        if finished {
+            for result in message.battle_result.values() {
+                battle.scores.insert(result.user_id, result.score);
+            }
+            battle.winner = message
+                .battle_result
+                .values()
+                .find(|result| result.result == BattleOutcome::Win as i32)
+                .map(|result| result.user_id);
+            if battle.status == BattleStatus::InProgress {
+                battle.status = BattleStatus::Finished;
+                return Some(TikTokLiveEvent::OnBattleFinished(battle.clone()));
+            }
+            return None;
+        }
+
+        if !battle.started_emitted {
+            battle.started_emitted = true;
+            return Some(TikTokLiveEvent::OnBattleStarted(battle.clone()));
+        }
+        Some(TikTokLiveEvent::OnBattleScoreUpdate(battle.clone()))
+    }
+
+    pub(crate) fn handle_armies(&self, message: &WebcastLinkMicArmies) -> Option<TikTokLiveEvent> {
+        let mut battles = self.battles.lock().unwrap();
+        let battle = battles
+            .entry(message.battle_id)
+            .or_insert_with(|| TikTokLiveBattle::new(message.battle_id));
+
+        if let Some(setting) = &message.battle_settings {
+            battle.apply_setting(setting);
+        }
+        battle.apply_armies(&message.battle_items);
+
+        if battle.status != BattleStatus::InProgress {
+            return None;
+        }
+        Some(TikTokLiveEvent::OnBattleScoreUpdate(battle.clone()))
+    }
+
+    pub(crate) fn handle_punish_finish(
+        &self,
+        message: &WebcastLinkMicBattlePunishFinish,
+    ) -> Option<TikTokLiveEvent> {
+        if let Some(battle) = self.battles.lock().unwrap().get_mut(&message.battle_id) {
+            battle.status = BattleStatus::PunishFinished;
+        }
+        Some(TikTokLiveEvent::OnBattlePunishFinished(message.clone()))
+    }
+}
+
+fn map_participants(message: &WebcastLinkMicBattle) -> Vec<BattleParticipant> {
+    let mut participants: Vec<BattleParticipant> = message
+        .anchor_info
+        .iter()
+        .map(|(user_id, info)| {
+            let user = info.user.as_ref();
+            BattleParticipant {
+                user_id: *user_id,
+                nickname: user.map(|u| u.nick_name.clone()).unwrap_or_default(),
+                display_id: user.map(|u| u.display_id.clone()).unwrap_or_default(),
+                team_id: message
+                    .team_users
+                    .iter()
+                    .find(|team| team.user_ids.contains(user_id))
+                    .map(|team| team.team_id),
+            }
+        })
+        .collect();
+    participants.sort_by_key(|participant| participant.user_id);
+    participants
+}
@@ -1,18 +1,28 @@
 // English comments for commits
 // This file is mostly correct and doesn't need many changes.
 // It orchestrates the creation of the client's components.
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
 use crate::core::live_client::TikTokLiveClient;
-use crate::core::live_client_events::{TikTokEventHandler, TikTokLiveEventObserver};
+use crate::core::live_client_events::{
+    TikTokEventHandler, TikTokHistoryEventHandler, TikTokLiveEventObserver,
+};
 use crate::core::live_client_http::TikTokLiveHttpClient;
 use crate::core::live_client_mapper::TikTokLiveMessageMapper;
+use crate::core::live_client_polling::TikTokLivePollingClient;
 use crate::core::live_client_websocket::TikTokLiveWebsocketClient;
 use crate::data::create_default_settings;
 use crate::data::live_common::{TikTokLiveInfo, TikTokLiveSettings};
+use crate::data::live_target::{parse_live_url, LiveUrl};
+use crate::errors::LibError;
 use crate::http::http_request_builder::HttpRequestFactory;
+use crate::http::http_signer::{EulerUrlSigner, UrlSigner};
 
 pub struct TikTokLiveBuilder {
     settings: TikTokLiveSettings,
     pub(crate) event_observer: TikTokLiveEventObserver,
+    signer: Option<Arc<dyn UrlSigner>>,
 }
 
 impl TikTokLiveBuilder {
@@ -22,6 +32,27 @@ impl TikTokLiveBuilder {
         Self {
             settings: create_default_settings(user_name),
             event_observer: TikTokLiveEventObserver::new(),
+            signer: None,
+        }
+    }
+
+    /// Creates a builder that connects to `room_id` directly, without the username lookup.
+    pub fn from_room_id(room_id: &str) -> Self {
+        let mut builder = Self::new("");
+        builder.settings.room_id = Some(room_id.trim().to_string());
+        builder
+    }
+
+    /// Creates a builder from a `tiktok.com/@user`, `tiktok.com/@user/live` or `vm.tiktok.com`
+    /// link. Short links are followed when connecting.
+    pub fn from_url(url: &str) -> Result<Self, LibError> {
+        match parse_live_url(url)? {
+            LiveUrl::User(user_name) => Ok(Self::new(&user_name)),
+            LiveUrl::ShareLink(link) => {
+                let mut builder = Self::new("");
+                builder.settings.share_link = Some(link);
+                Ok(builder)
+            }
         }
     }
 
@@ -40,18 +71,42 @@ impl TikTokLiveBuilder {
         self
     }
 
+    /// Subscribes an event handler that is also told whether the event is history, i.e. one of
+    /// the messages from before connecting that TikTok sends right after `OnConnected`.
+    pub fn on_event_with_history(&mut self, on_event: TikTokHistoryEventHandler) -> &mut Self {
+        self.event_observer.subscribe_with_history(on_event);
+        self
+    }
+
+    /// Replaces the default Euler Stream signer, e.g. with a self-hosted or mock signer.
+    pub fn with_signer<S>(&mut self, signer: S) -> &mut Self
+    where
+        S: UrlSigner + 'static,
+    {
+        self.signer = Some(Arc::new(signer));
+        self
+    }
+
     /// Builds the final `TikTokLiveClient` instance.
     pub fn build(&self) -> TikTokLiveClient {
-        let settings = self.settings.clone();
+        let mut settings = self.settings.clone();
+        if let Some(pool) = &settings.proxy_pool {
+            settings.http_data.proxy = pool.next_proxy();
+        }
         let observer = self.event_observer.clone();
         let mapper = TikTokLiveMessageMapper {};
+        let polling_client = TikTokLivePollingClient::new(mapper.clone());
         let websocket_client = TikTokLiveWebsocketClient::new(mapper);
-        let http_factory = HttpRequestFactory {
-            settings: settings.clone(),
-        };
+        let http_factory = HttpRequestFactory::new(settings.clone());
+        let signer = self
+            .signer
+            .clone()
+            .unwrap_or_else(|| Arc::new(EulerUrlSigner::new(&settings)));
         let http_client = TikTokLiveHttpClient {
             settings: settings.clone(),
             factory: http_factory,
+            signer,
+            authenticated: AtomicBool::new(false),
         };
 
         TikTokLiveClient::new(
@@ -59,6 +114,7 @@ impl TikTokLiveBuilder {
             http_client,
             observer,
             websocket_client,
+            polling_client,
             TikTokLiveInfo::default(),
         )
     }
@@ -0,0 +1,239 @@
+// live_client_caption.rs
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::warn;
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::data::live_common::{CaptionExport, CaptionFormat};
+use crate::generated::WebcastCaptionMessage;
+
+/// A caption sentence that is still being recognized.
+struct PendingSentence {
+    sequence_id: i64,
+    timestamp_ms: i64,
+    duration_ms: i64,
+    /// Latest text per language.
+    content: HashMap<String, String>,
+}
+
+/// An open transcript file for one language.
+struct CaptionFile {
+    file: File,
+    next_cue: u64,
+}
+
+#[derive(Default)]
+struct CaptionState {
+    /// Timestamp of the first caption, used as `00:00:00` of the transcript.
+    origin_ms: Option<i64>,
+    pending: BTreeMap<i64, PendingSentence>,
+    /// Highest sentence already published, later fragments of it are repeats.
+    last_finished: Option<i64>,
+    files: HashMap<String, CaptionFile>,
+}
+
+/// Merges `WebcastCaptionMessage` fragments into final sentences and optionally
+/// archives them as SRT or WebVTT files, one per language.
+pub struct TikTokLiveCaptionAssembler {
+    export: Option<CaptionExport>,
+    file_prefix: String,
+    state: Mutex<CaptionState>,
+}
+
+impl TikTokLiveCaptionAssembler {
+    pub fn new(export: Option<CaptionExport>, file_prefix: &str) -> Self {
+        TikTokLiveCaptionAssembler {
+            export,
+            file_prefix: file_prefix.to_string(),
+            state: Mutex::new(CaptionState::default()),
+        }
+    }
+
+    /// Names the transcript files after a username that was only known after building.
+    pub(crate) fn set_file_prefix(&mut self, file_prefix: &str) {
+        self.file_prefix = file_prefix.to_string();
+    }
+
+    pub(crate) fn handle_caption(&self, message: &WebcastCaptionMessage) -> Vec<TikTokLiveEvent> {
+        let mut state = self.state.lock().unwrap();
+        if state
+            .last_finished
+            .is_some_and(|last_finished| message.sentence_id <= last_finished)
+        {
+            return vec![];
+        }
+        state.origin_ms.get_or_insert(message.timestamp_ms);
+
+        let pending = state
+            .pending
+            .entry(message.sentence_id)
+            .or_insert_with(|| PendingSentence {
+                sequence_id: i64::MIN,
+                timestamp_ms: message.timestamp_ms,
+                duration_ms: 0,
+                content: HashMap::new(),
+            });
+        // Fragments may arrive out of order, only a newer one replaces the text.
+        if message.sequence_id >= pending.sequence_id {
+            pending.sequence_id = message.sequence_id;
+            pending.duration_ms = message.timestamp_ms + message.duration_ms - pending.timestamp_ms;
+            for caption in &message.content {
+                pending
+                    .content
+                    .insert(caption.lang.clone(), caption.content.clone());
+            }
+        }
+
+        // A newer definite sentence means older unfinished ones will not be completed anymore.
+        let finished: Vec<i64> = if message.definite {
+            state
+                .pending
+                .range(..=message.sentence_id)
+                .map(|(sentence_id, _)| *sentence_id)
+                .collect()
+        } else {
+            state
+                .pending
+                .range(..message.sentence_id)
+                .map(|(sentence_id, _)| *sentence_id)
+                .collect()
+        };
+
+        let mut events = vec![];
+        for sentence_id in finished {
+            if let Some(sentence) = state.pending.remove(&sentence_id) {
+                events.extend(self.finish_sentence(&mut state, sentence_id, sentence));
+            }
+        }
+        events
+    }
+
+    /// Finishes all sentences still waiting for their final fragment, called when the
+    /// transport stops so the last line also reaches the transcript.
+    pub(crate) fn flush(&self) -> Vec<TikTokLiveEvent> {
+        let mut state = self.state.lock().unwrap();
+        let pending = std::mem::take(&mut state.pending);
+        let mut events = vec![];
+        for (sentence_id, sentence) in pending {
+            events.extend(self.finish_sentence(&mut state, sentence_id, sentence));
+        }
+        for (lang, caption_file) in state.files.iter_mut() {
+            if let Err(e) = caption_file.file.flush() {
+                warn!("Failed to flush captions for language '{}': {}", lang, e);
+            }
+        }
+        events
+    }
+
+    fn finish_sentence(
+        &self,
+        state: &mut CaptionState,
+        sentence_id: i64,
+        sentence: PendingSentence,
+    ) -> Vec<TikTokLiveEvent> {
+        state.last_finished = Some(
+            state
+                .last_finished
+                .map_or(sentence_id, |last| last.max(sentence_id)),
+        );
+        let origin_ms = state.origin_ms.unwrap_or(sentence.timestamp_ms);
+        let start = Duration::from_millis((sentence.timestamp_ms - origin_ms).max(0) as u64);
+        let end = start + Duration::from_millis(sentence.duration_ms.max(0) as u64);
+
+        let mut languages: Vec<(String, String)> = sentence
+            .content
+            .into_iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .collect();
+        languages.sort();
+
+        languages
+            .into_iter()
+            .map(|(lang, text)| {
+                if let Some(export) = &self.export {
+                    if let Err(e) = self.write_cue(state, export, &lang, &text, start, end) {
+                        warn!("Failed to write caption for language '{}': {}", lang, e);
+                    }
+                }
+                TikTokLiveEvent::OnCaptionSentence {
+                    lang,
+                    text,
+                    start,
+                    end,
+                }
+            })
+            .collect()
+    }
+
+    fn write_cue(
+        &self,
+        state: &mut CaptionState,
+        export: &CaptionExport,
+        lang: &str,
+        text: &str,
+        start: Duration,
+        end: Duration,
+    ) -> std::io::Result<()> {
+        if !state.files.contains_key(lang) {
+            fs::create_dir_all(&export.directory)?;
+            let path = self.file_path(export, state.origin_ms.unwrap_or_default(), lang);
+            let is_new = !path.exists();
+            let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+            if is_new && export.format == CaptionFormat::WebVtt {
+                writeln!(file, "WEBVTT\n")?;
+            }
+            state
+                .files
+                .insert(lang.to_string(), CaptionFile { file, next_cue: 1 });
+        }
+        let caption_file = state.files.get_mut(lang).unwrap();
+        let cue = match export.format {
+            CaptionFormat::Srt => format!(
+                "{}\n{} --> {}\n{}\n\n",
+                caption_file.next_cue,
+                format_timestamp(start, ','),
+                format_timestamp(end, ','),
+                text
+            ),
+            CaptionFormat::WebVtt => format!(
+                "{} --> {}\n{}\n\n",
+                format_timestamp(start, '.'),
+                format_timestamp(end, '.'),
+                text
+            ),
+        };
+        caption_file.file.write_all(cue.as_bytes())?;
+        caption_file.next_cue += 1;
+        Ok(())
+    }
+
+    /// `<prefix>_<first caption timestamp>_<lang>.<ext>`, so every session gets its own files.
+    fn file_path(&self, export: &CaptionExport, origin_ms: i64, lang: &str) -> PathBuf {
+        let extension = match export.format {
+            CaptionFormat::Srt => "srt",
+            CaptionFormat::WebVtt => "vtt",
+        };
+        export.directory.join(format!(
+            "{}_{}_{}.{}",
+            self.file_prefix, origin_ms, lang, extension
+        ))
+    }
+}
+
+/// Formats `HH:MM:SS<separator>mmm`, `,` for SRT and `.` for WebVTT.
+fn format_timestamp(time: Duration, separator: char) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
@@ -0,0 +1,104 @@
+// live_client_chat.rs
+use crate::generated::{User, WebcastChatMessage, WebcastEmoteChatMessage};
+
+/// A piece of a chat comment, in the order it should be rendered.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum ChatSegment {
+    Text(String),
+    Emote { id: String, image_url: String },
+    Mention(User),
+}
+
+impl WebcastChatMessage {
+    /// Splits `comment` into text, subscriber emotes and the `at_user` mention.
+    pub fn segments(&self) -> Vec<ChatSegment> {
+        let chars: Vec<char> = self.comment.chars().collect();
+        let mut emotes: Vec<(usize, ChatSegment)> = self
+            .emotes
+            .iter()
+            .filter_map(|sub_emote| {
+                let details = sub_emote.emote.as_ref()?;
+                let image_url = details
+                    .image
+                    .as_ref()
+                    .map(|image| image.image_url.clone())
+                    .unwrap_or_default();
+                let index = (sub_emote.place_in_comment.max(0) as usize).min(chars.len());
+                Some((
+                    index,
+                    ChatSegment::Emote {
+                        id: details.emote_id.clone(),
+                        image_url,
+                    },
+                ))
+            })
+            .collect();
+        emotes.sort_by_key(|(index, _)| *index);
+
+        let mut segments = vec![];
+        let mut start = 0;
+        for (index, emote) in emotes {
+            push_text(&mut segments, chars[start..index].iter().collect());
+            segments.push(emote);
+            start = index;
+        }
+        push_text(&mut segments, chars[start..].iter().collect());
+
+        if let Some(user) = &self.at_user {
+            insert_mention(&mut segments, user);
+        }
+        segments
+    }
+}
+
+impl WebcastEmoteChatMessage {
+    /// Returns the emotes of this message as `ChatSegment::Emote` pieces.
+    pub fn segments(&self) -> Vec<ChatSegment> {
+        self.emote_list
+            .iter()
+            .map(|emote| ChatSegment::Emote {
+                id: emote.emote_id.clone(),
+                image_url: emote
+                    .image
+                    .as_ref()
+                    .and_then(|image| image.url.first().cloned())
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+fn push_text(segments: &mut Vec<ChatSegment>, text: String) {
+    if !text.is_empty() {
+        segments.push(ChatSegment::Text(text));
+    }
+}
+
+/// Replaces the first `@handle` of `user` in the text with a mention, or prepends one.
+fn insert_mention(segments: &mut Vec<ChatSegment>, user: &User) {
+    let handles = [&user.unique_id, &user.nickname];
+    for (position, segment) in segments.iter().enumerate() {
+        let ChatSegment::Text(text) = segment else {
+            continue;
+        };
+        let found = handles
+            .iter()
+            .filter(|handle| !handle.is_empty())
+            .find_map(|handle| {
+                let tag = format!("@{}", handle);
+                text.find(&tag).map(|at| (at, at + tag.len()))
+            });
+        if let Some((at, end)) = found {
+            let before = text[..at].to_string();
+            let after = text[end..].to_string();
+            let mut replacement = vec![];
+            push_text(&mut replacement, before);
+            replacement.push(ChatSegment::Mention(user.clone()));
+            push_text(&mut replacement, after);
+            segments.splice(position..=position, replacement);
+            return;
+        }
+    }
+    segments.insert(0, ChatSegment::Mention(user.clone()));
+}
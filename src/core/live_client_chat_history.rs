@@ -0,0 +1,56 @@
+// live_client_chat_history.rs
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::{WebcastChatMessage, WebcastImDeleteMessage};
+
+/// Bounded buffer of the latest chat messages with moderator deletions applied.
+pub struct TikTokLiveChatHistory {
+    capacity: usize,
+    messages: Mutex<VecDeque<WebcastChatMessage>>,
+}
+
+impl TikTokLiveChatHistory {
+    pub fn new(capacity: usize) -> Self {
+        TikTokLiveChatHistory {
+            capacity,
+            messages: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns the buffered chat messages, oldest first.
+    pub fn get_messages(&self) -> Vec<WebcastChatMessage> {
+        self.messages.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub(crate) fn push(&self, message: &WebcastChatMessage) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut messages = self.messages.lock().unwrap();
+        if messages.len() == self.capacity {
+            messages.pop_front();
+        }
+        messages.push_back(message.clone());
+    }
+
+    pub(crate) fn handle_delete(
+        &self,
+        message: &WebcastImDeleteMessage,
+    ) -> Option<TikTokLiveEvent> {
+        if message.delete_msg_ids_list.is_empty() && message.delete_user_ids_list.is_empty() {
+            return None;
+        }
+        self.messages.lock().unwrap().retain(|chat| {
+            let msg_id = chat.common.as_ref().map(|common| common.msg_id);
+            let user_id = chat.user.as_ref().map(|user| user.user_id);
+            !msg_id.is_some_and(|id| message.delete_msg_ids_list.contains(&id))
+                && !user_id.is_some_and(|id| message.delete_user_ids_list.contains(&id))
+        });
+        Some(TikTokLiveEvent::OnMessagesDeleted {
+            message_ids: message.delete_msg_ids_list.clone(),
+            user_ids: message.delete_user_ids_list.clone(),
+        })
+    }
+}
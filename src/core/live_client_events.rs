@@ -1,13 +1,27 @@
 // live_client_events.rs
 // English comments for the commit
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
 use crate::core::live_client::TikTokLiveClient;
+use crate::core::live_client_barrage::TikTokLiveBarrage;
+use crate::core::live_client_battle::TikTokLiveBattle;
+use crate::core::live_client_goal::TikTokLiveGoal;
+use crate::core::live_client_guest::TikTokLiveGuest;
+use crate::core::live_client_pin::PinnedContent;
+use crate::core::live_client_poll::PollOption;
+use crate::core::live_client_question::TikTokLiveQuestion;
+use crate::core::live_client_shopping::TikTokLiveProduct;
+use crate::core::live_client_text::render_common;
+use crate::core::live_client_treasure::TikTokLiveTreasureChest;
 // Import the new, prost-generated structs.
 // We import the specific message types we will handle.
 // NOTE: The exact names (e.g., `WebcastChatMessage`, `WebcastGiftMessage`) come directly
 // from the .proto files. We are effectively re-mapping the old event system to the new one.
 use crate::generated::{
-    WebcastChatMessage, WebcastGiftMessage, WebcastLikeMessage, WebcastMemberMessage,
+    CommonMessageData, User, WebcastChatMessage, WebcastEmoteChatMessage, WebcastGiftMessage,
+    WebcastInRoomBannerMessage, WebcastLikeMessage, WebcastLinkMicBattlePunishFinish,
+    WebcastLiveIntroMessage, WebcastMemberMessage, WebcastRoomMessage,
 };
 
 // --- Refactoring Step 1: Create a new Event Enum ---
@@ -15,43 +29,182 @@ use crate::generated::{
 // providing a clean, unified interface for the rest of the application.
 // This replaces the old, non-existent `TikTokLiveEvent`.
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum TikTokLiveEvent {
     OnChatMessage(WebcastChatMessage),
     OnGiftMessage(WebcastGiftMessage),
     OnLikeMessage(WebcastLikeMessage),
     OnMemberMessage(WebcastMemberMessage),
+    OnEmoteChat(WebcastEmoteChatMessage),
     // We can add other events here later as we implement them.
     // e.g., OnSocialMessage(WebcastSocialMessage),
+    OnBattleStarted(TikTokLiveBattle),
+    OnBattleScoreUpdate(TikTokLiveBattle),
+    OnBattleFinished(TikTokLiveBattle),
+    OnBattlePunishFinished(WebcastLinkMicBattlePunishFinish),
+    OnPollStarted {
+        poll_id: i64,
+        title: String,
+        options: Vec<PollOption>,
+        duration: Duration,
+    },
+    OnPollVotesUpdated {
+        poll_id: i64,
+        /// Votes per `option_id`.
+        votes: HashMap<i32, i32>,
+    },
+    OnPollEnded {
+        poll_id: i64,
+        winner: Option<PollOption>,
+        /// Final votes per `option_id`.
+        totals: HashMap<i32, i32>,
+    },
+    OnGoalUpdate(TikTokLiveGoal),
+    OnTreasureChest(TikTokLiveTreasureChest),
+    OnCaptionSentence {
+        lang: String,
+        text: String,
+        /// Offset from the first caption received in this session.
+        start: Duration,
+        end: Duration,
+    },
+    OnQuestion(TikTokLiveQuestion),
+    OnRankUpdate {
+        list: String,
+        old_position: Option<i64>,
+        new_position: Option<i64>,
+        text: String,
+    },
+    /// A moderator deleted comments or banned users, their messages should be hidden.
+    OnMessagesDeleted {
+        message_ids: Vec<i64>,
+        user_ids: Vec<i64>,
+    },
+    OnMessagePinned {
+        pin_id: i64,
+        operator: Option<User>,
+        pinned: PinnedContent,
+        expires_at: Option<SystemTime>,
+    },
+    OnMessageUnpinned {
+        pin_id: i64,
+        operator: Option<User>,
+    },
+    OnShoppingProduct(TikTokLiveProduct),
+    OnGuestJoined(TikTokLiveGuest),
+    OnGuestLeft(TikTokLiveGuest),
+    OnGuestMuteChanged(TikTokLiveGuest),
+    OnLiveIntro(WebcastLiveIntroMessage),
+    OnRoomBanner(WebcastInRoomBannerMessage),
+    OnRoomNotice(WebcastRoomMessage),
+    OnBarrage(TikTokLiveBarrage),
+    /// A message type without a dedicated event, `payload` is the undecoded protobuf.
+    OnOtherMessage {
+        method: String,
+        common: Option<CommonMessageData>,
+        payload: Vec<u8>,
+    },
     OnConnected,
     OnDisconnected,
 }
 
+impl TikTokLiveEvent {
+    /// The text the TikTok app would show for this event, e.g. `"user joined"`.
+    pub fn display_text(&self) -> Option<String> {
+        match self {
+            TikTokLiveEvent::OnChatMessage(msg) => render_common(msg.common.as_ref())
+                .or_else(|| Some(format!("{}: {}", nickname(msg.user.as_ref()), msg.comment))),
+            TikTokLiveEvent::OnGiftMessage(msg) => render_common(msg.common.as_ref()),
+            TikTokLiveEvent::OnLikeMessage(msg) => render_common(msg.common.as_ref()),
+            TikTokLiveEvent::OnMemberMessage(msg) => render_common(msg.common.as_ref())
+                .or_else(|| Some(format!("{} joined", nickname(msg.user.as_ref())))),
+            TikTokLiveEvent::OnEmoteChat(msg) => render_common(msg.common.as_ref()),
+            TikTokLiveEvent::OnBattlePunishFinished(msg) => render_common(msg.common.as_ref()),
+            TikTokLiveEvent::OnPollStarted { title, .. } => Some(title.clone()),
+            TikTokLiveEvent::OnGoalUpdate(goal) => Some(goal.description.clone()),
+            TikTokLiveEvent::OnTreasureChest(chest) => Some(format!(
+                "{} sent a treasure chest with {} diamonds",
+                chest.sender_name, chest.diamond_count
+            )),
+            TikTokLiveEvent::OnCaptionSentence { text, .. } => Some(text.clone()),
+            TikTokLiveEvent::OnQuestion(question) => Some(question.text.clone()),
+            TikTokLiveEvent::OnRankUpdate { text, .. } => Some(text.clone()),
+            TikTokLiveEvent::OnShoppingProduct(product) => Some(product.title.clone()),
+            TikTokLiveEvent::OnGuestJoined(guest) => {
+                Some(format!("{} joined as a guest", nickname(guest.user.as_ref())))
+            }
+            TikTokLiveEvent::OnGuestLeft(guest) => {
+                Some(format!("{} left the guest seat", nickname(guest.user.as_ref())))
+            }
+            TikTokLiveEvent::OnLiveIntro(msg) => render_common(msg.common.as_ref())
+                .or_else(|| Some(msg.description.clone())),
+            TikTokLiveEvent::OnRoomBanner(msg) => render_common(msg.common.as_ref()),
+            TikTokLiveEvent::OnRoomNotice(msg) => render_common(msg.common.as_ref())
+                .or_else(|| Some(msg.content.clone())),
+            TikTokLiveEvent::OnBarrage(barrage) => Some(barrage.display_text.clone()),
+            TikTokLiveEvent::OnOtherMessage { common, .. } => render_common(common.as_ref()),
+            _ => None,
+        }
+        .filter(|text| !text.is_empty())
+    }
+}
+
+fn nickname(user: Option<&User>) -> &str {
+    user.map(|user| user.nickname.as_str()).unwrap_or_default()
+}
+
 // --- Refactoring Step 2: Update the EventHandler Type Alias ---
 // The type alias now uses our new `TikTokLiveEvent` enum.
 pub type TikTokEventHandler =
     fn(client: &TikTokLiveClient, event: &TikTokLiveEvent);
 
+/// Event handler that is also told whether the event replays a message from before the
+/// client connected, e.g. the recent chats TikTok sends right after `OnConnected`.
+pub type TikTokHistoryEventHandler =
+    fn(client: &TikTokLiveClient, event: &TikTokLiveEvent, is_history: bool);
+
 // The observer struct itself doesn't need to change its structure.
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct TikTokLiveEventObserver {
     pub events: Vec<TikTokEventHandler>,
+    pub history_events: Vec<TikTokHistoryEventHandler>,
 }
 
 impl TikTokLiveEventObserver {
     pub fn new() -> Self {
-        TikTokLiveEventObserver { events: vec![] }
+        TikTokLiveEventObserver {
+            events: vec![],
+            history_events: vec![],
+        }
     }
 
     pub fn subscribe(&mut self, handler: TikTokEventHandler) {
         self.events.push(handler);
     }
 
+    pub fn subscribe_with_history(&mut self, handler: TikTokHistoryEventHandler) {
+        self.history_events.push(handler);
+    }
+
     // --- Refactoring Step 3: Update the `publish` method signature ---
     // This method now accepts our new `TikTokLiveEvent` enum.
     pub fn publish(&self, client: &TikTokLiveClient, event: TikTokLiveEvent) {
+        self.publish_with_history(client, event, false);
+    }
+
+    /// Publishes `event` with its history flag, handlers on other tasks see the same flag.
+    pub fn publish_with_history(
+        &self,
+        client: &TikTokLiveClient,
+        event: TikTokLiveEvent,
+        is_history: bool,
+    ) {
         for handler in &self.events {
             // The logic inside remains the same, but the type is now correct.
             handler(client, &event);
         }
+        for handler in &self.history_events {
+            handler(client, &event, is_history);
+        }
     }
 }
\ No newline at end of file
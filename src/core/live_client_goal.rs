@@ -0,0 +1,114 @@
+// live_client_goal.rs
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::{LiveStreamGoal, WebcastGoalUpdateMessage};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub sub_goal_id: i64,
+    /// Name of the gift counted towards this sub-goal, empty for non-gift goals.
+    pub gift_name: String,
+    pub progress: i64,
+    pub target: i64,
+}
+
+impl GoalProgress {
+    pub fn is_complete(&self) -> bool {
+        self.target > 0 && self.progress >= self.target
+    }
+}
+
+/// Progress of a LIVE goal (e.g. "1000 Roses") as reported by `WebcastGoalUpdateMessage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLiveGoal {
+    pub goal_id: i64,
+    pub description: String,
+    pub sub_goals: Vec<GoalProgress>,
+    pub start_time: i64,
+    /// Unix timestamp in seconds, `0` when the goal does not expire.
+    pub expire_time: i64,
+    pub finished: bool,
+    pub pinned: bool,
+}
+
+impl TikTokLiveGoal {
+    fn from_goal(goal: &LiveStreamGoal) -> Self {
+        let sub_goals: Vec<GoalProgress> = goal
+            .sub_goals
+            .iter()
+            .map(|sub_goal| GoalProgress {
+                sub_goal_id: sub_goal.id,
+                gift_name: sub_goal
+                    .gift
+                    .as_ref()
+                    .map(|gift| gift.name.clone())
+                    .unwrap_or_default(),
+                progress: sub_goal.progress,
+                target: sub_goal.target,
+            })
+            .collect();
+        let finished = goal.real_finish_time > 0
+            || (!sub_goals.is_empty() && sub_goals.iter().all(GoalProgress::is_complete));
+
+        TikTokLiveGoal {
+            goal_id: goal.id,
+            description: goal.description.clone(),
+            sub_goals,
+            start_time: goal.start_time,
+            expire_time: goal.expire_time,
+            finished,
+            pinned: false,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        if self.finished {
+            return false;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        self.expire_time == 0 || self.expire_time > now
+    }
+}
+
+/// Keeps the latest progress of every goal seen in the room.
+#[derive(Default)]
+pub struct TikTokLiveGoalTracker {
+    goals: Mutex<HashMap<i64, TikTokLiveGoal>>,
+}
+
+impl TikTokLiveGoalTracker {
+    /// Returns the goals that are neither finished nor expired.
+    pub fn get_active_goals(&self) -> Vec<TikTokLiveGoal> {
+        let mut goals: Vec<TikTokLiveGoal> = self
+            .goals
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|goal| goal.is_active())
+            .cloned()
+            .collect();
+        goals.sort_by_key(|goal| goal.goal_id);
+        goals
+    }
+
+    pub(crate) fn handle_goal_update(
+        &self,
+        message: &WebcastGoalUpdateMessage,
+    ) -> Option<TikTokLiveEvent> {
+        let goal = message.goal.as_ref()?;
+        let mut goals = self.goals.lock().unwrap();
+        let was_pinned = goals.get(&goal.id).map(|g| g.pinned).unwrap_or_default();
+
+        let mut tracked = TikTokLiveGoal::from_goal(goal);
+        tracked.pinned = (was_pinned || message.pin) && !message.unpin;
+        goals.insert(tracked.goal_id, tracked.clone());
+
+        Some(TikTokLiveEvent::OnGoalUpdate(tracked))
+    }
+}
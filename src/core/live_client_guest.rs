@@ -0,0 +1,184 @@
+// live_client_guest.rs
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::{
+    LinkLayerListUser, LinkSilenceStatus, ListUser, MuteStatus, User, WebcastLinkLayerMessage,
+    WebcastLinkMessage,
+};
+
+/// A guest or co-host currently on a mic seat in the room.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLiveGuest {
+    pub user_id: i64,
+    pub user: Option<User>,
+    pub linkmic_id: i64,
+    /// Mic position, `None` when TikTok did not send one.
+    pub position: Option<i32>,
+    pub muted: bool,
+}
+
+impl TikTokLiveGuest {
+    fn from_list_user(list_user: &ListUser) -> Option<Self> {
+        let user = list_user.user.as_ref()?;
+        Some(TikTokLiveGuest {
+            user_id: user.user_id,
+            user: Some(user.clone()),
+            linkmic_id: list_user.linkmic_id,
+            position: Some(list_user.user_position),
+            muted: list_user.silence_status != LinkSilenceStatus::StatusUnSilence as i32,
+        })
+    }
+
+    fn from_layer_user(layer_user: &LinkLayerListUser) -> Option<Self> {
+        let user = layer_user.user.as_ref()?;
+        Some(TikTokLiveGuest {
+            user_id: user.user_id,
+            user: Some(user.clone()),
+            linkmic_id: layer_user.linkmic_id,
+            position: layer_user
+                .pos
+                .as_ref()
+                .and_then(|pos| pos.link.as_ref())
+                .map(|link| link.position),
+            muted: false,
+        })
+    }
+}
+
+/// Keeps the guest seats of multi-guest and co-host sessions from
+/// `WebcastLinkMessage` and `WebcastLinkLayerMessage`.
+#[derive(Default)]
+pub struct TikTokLiveGuestTracker {
+    guests: Mutex<HashMap<i64, TikTokLiveGuest>>,
+}
+
+impl TikTokLiveGuestTracker {
+    /// Returns the current guests ordered by mic position.
+    pub fn get_guests(&self) -> Vec<TikTokLiveGuest> {
+        let mut guests: Vec<TikTokLiveGuest> =
+            self.guests.lock().unwrap().values().cloned().collect();
+        guests.sort_by_key(|guest| (guest.position.unwrap_or(i32::MAX), guest.user_id));
+        guests
+    }
+
+    pub(crate) fn handle_link(&self, message: &WebcastLinkMessage) -> Vec<TikTokLiveEvent> {
+        let mut events = vec![];
+        if let Some(content) = &message.enter_content {
+            let guests = content
+                .linked_users_list
+                .iter()
+                .filter_map(TikTokLiveGuest::from_list_user);
+            for guest in guests {
+                events.extend(self.join(guest));
+            }
+        }
+        if let Some(content) = &message.list_change_content {
+            let guests = content
+                .linked_users
+                .iter()
+                .filter_map(TikTokLiveGuest::from_list_user)
+                .collect();
+            events.extend(self.replace_all(guests));
+        }
+        if let Some(content) = &message.leave_content {
+            events.extend(self.leave(content.user_id));
+        }
+        if let Some(content) = &message.mute_content {
+            let muted = content.status == MuteStatus::Mute as i32;
+            events.extend(self.set_muted(content.user_id, muted));
+        }
+        events
+    }
+
+    pub(crate) fn handle_link_layer(
+        &self,
+        message: &WebcastLinkLayerMessage,
+    ) -> Vec<TikTokLiveEvent> {
+        let mut events = vec![];
+        if let Some(user_list) = message
+            .list_change_content
+            .as_ref()
+            .and_then(|content| content.user_list.as_ref())
+        {
+            let guests = user_list
+                .linked_list
+                .iter()
+                .filter_map(TikTokLiveGuest::from_layer_user)
+                .collect();
+            events.extend(self.replace_all(guests));
+        }
+        if let Some(joiner) = message
+            .join_direct_content
+            .as_ref()
+            .and_then(|content| content.joiner.as_ref())
+            .and_then(TikTokLiveGuest::from_layer_user)
+        {
+            events.extend(self.join(joiner));
+        }
+        if let Some(leaver) = message
+            .leave_content
+            .as_ref()
+            .and_then(|content| content.leaver.as_ref())
+        {
+            events.extend(self.leave(leaver.user_id));
+        }
+        if let Some(offliner) = message
+            .kick_out_content
+            .as_ref()
+            .and_then(|content| content.offliner.as_ref())
+        {
+            events.extend(self.leave(offliner.user_id));
+        }
+        events
+    }
+
+    fn join(&self, guest: TikTokLiveGuest) -> Option<TikTokLiveEvent> {
+        let mut guests = self.guests.lock().unwrap();
+        let is_new = !guests.contains_key(&guest.user_id);
+        let entry = guests.entry(guest.user_id).or_insert_with(|| guest.clone());
+        // Mute changes arrive as their own messages, so a known guest keeps its mute state.
+        if !is_new {
+            entry.position = guest.position.or(entry.position);
+            entry.user = guest.user.or(entry.user.take());
+            entry.linkmic_id = guest.linkmic_id;
+            return None;
+        }
+        Some(TikTokLiveEvent::OnGuestJoined(entry.clone()))
+    }
+
+    fn leave(&self, user_id: i64) -> Option<TikTokLiveEvent> {
+        let guest = self.guests.lock().unwrap().remove(&user_id)?;
+        Some(TikTokLiveEvent::OnGuestLeft(guest))
+    }
+
+    fn set_muted(&self, user_id: i64, muted: bool) -> Option<TikTokLiveEvent> {
+        let mut guests = self.guests.lock().unwrap();
+        let guest = guests.get_mut(&user_id)?;
+        if guest.muted == muted {
+            return None;
+        }
+        guest.muted = muted;
+        Some(TikTokLiveEvent::OnGuestMuteChanged(guest.clone()))
+    }
+
+    /// Applies a full seat list: unknown guests join, missing guests leave.
+    fn replace_all(&self, list: Vec<TikTokLiveGuest>) -> Vec<TikTokLiveEvent> {
+        let left: Vec<i64> = self
+            .guests
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|user_id| !list.iter().any(|guest| guest.user_id == **user_id))
+            .cloned()
+            .collect();
+
+        let mut events: Vec<TikTokLiveEvent> = left
+            .into_iter()
+            .filter_map(|user_id| self.leave(user_id))
+            .collect();
+        events.extend(list.into_iter().filter_map(|guest| self.join(guest)));
+        events
+    }
+}
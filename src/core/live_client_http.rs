@@ -1,24 +1,33 @@
 // live_client_http.rs
 // Replace the old `protobuf` import with the new `prost` import.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use prost::Message;
 
-use crate::data::live_common::TikTokLiveSettings;
+use crate::data::live_common::{ProxyConfig, RoomIdResolver, TikTokLiveSettings};
+use crate::data::live_target::{parse_live_url, LiveUrl};
 use crate::errors::LibError;
 // Import the `WebcastResponse` struct directly from our new `generated` module.
 // The old path `...::messages::webcast::` is no longer valid.
 use crate::generated::ProtoMessageFetchResult;
 use crate::http::http_data::{
     LiveConnectionDataRequest, LiveConnectionDataResponse, LiveDataRequest, LiveDataResponse,
-    LiveUserDataRequest, LiveUserDataResponse,
+    LiveUserDataRequest, LiveUserDataResponse, SignServerResponse,
 };
 use crate::http::http_data_mappers::{
-    map_live_data_response, map_live_user_data_response, map_sign_server_response,
+    map_live_data_response, map_live_page_response, map_live_user_data_response,
 };
-use crate::http::http_request_builder::HttpRequestFactory;
+use crate::http::http_request_builder::{HttpRequestBuilder, HttpRequestFactory};
+use crate::http::http_retry::{RateLimiter, RetryPolicy};
+use crate::http::http_signer::UrlSigner;
 
 pub struct TikTokLiveHttpClient {
     pub(crate) settings: TikTokLiveSettings,
     pub(crate) factory: HttpRequestFactory,
+    pub(crate) signer: Arc<dyn UrlSigner>,
+    pub(crate) authenticated: AtomicBool,
 }
 
 pub const TIKTOK_URL_WEB: &str = "https://www.tiktok.com/";
@@ -28,38 +37,142 @@ pub const TIKTOK_URL_WEBCAST: &str = "https://webcast.tiktok.com/webcast/";
 pub const TIKTOK_SIGN_API: &str = "https://tiktok.eulerstream.com/webcast/sign_url";
 
 impl TikTokLiveHttpClient {
+    /// Sends all further requests, sign requests included, through `proxy`.
+    pub(crate) fn use_proxy(&mut self, proxy: Option<ProxyConfig>) {
+        self.settings.http_data.proxy = proxy.clone();
+        self.factory.set_proxy(proxy.clone());
+        self.signer.set_proxy(proxy);
+    }
+
+    /// Puts the session cookies into the jar, so HTTP requests and the websocket use them, and
+    /// hands the session to the signer. The jar keeps them in memory only.
+    ///
+    /// Returns `false` when there is no session or it was already applied.
+    pub(crate) fn authenticate(&self) -> bool {
+        let Some(session) = &self.settings.session else {
+            return false;
+        };
+        if self.authenticated.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        let jar = &self.factory.cookie_jar;
+        jar.insert("sessionid", &session.session_id);
+        jar.insert("sessionid_ss", &session.session_id);
+        jar.insert("sid_tt", &session.session_id);
+        if !session.tt_target_idc.is_empty() {
+            jar.insert("tt-target-idc", &session.tt_target_idc);
+        }
+        self.signer.set_session(Some(session.clone()));
+        true
+    }
+
     pub async fn fetch_live_user_data(
         &self,
         request: LiveUserDataRequest,
     ) -> Result<LiveUserDataResponse, LibError> {
         let url = format!("{}{}", TIKTOK_URL_WEB, "api-live/user/room");
-        let option = self
-            .factory
-            .request()
+        let mut request_builder = self.factory.request();
+        request_builder
             .with_url(url.as_str())
             .with_param("uniqueId", &request.user_name)
-            .with_param("sourceType", "54")
-            .as_json()
-            .await;
+            .with_param("sourceType", "54");
 
-        let json = option.ok_or(LibError::HttpRequestFailed)?;
+        let response = self
+            .send_with_retry(
+                &mut request_builder,
+                self.settings.rate_limits.tiktok.as_ref(),
+                &self.settings.retry.user_data,
+            )
+            .await?;
+        let json = response.text().await.map_err(|_| LibError::HttpRequestFailed)?;
         map_live_user_data_response(json)
     }
 
+    /// Fetches the `tiktok.com/@{user}/live` page and reads the room id from its state.
+    pub async fn fetch_live_page_data(
+        &self,
+        request: LiveUserDataRequest,
+    ) -> Result<LiveUserDataResponse, LibError> {
+        let url = format!("{}@{}/live", TIKTOK_URL_WEB, request.user_name);
+        let mut request_builder = self.factory.request();
+        request_builder.with_url(url.as_str());
+
+        let response = self
+            .send_with_retry(
+                &mut request_builder,
+                self.settings.rate_limits.tiktok.as_ref(),
+                &self.settings.retry.user_data,
+            )
+            .await?;
+        let html = response.text().await.map_err(|_| LibError::HttpRequestFailed)?;
+        map_live_page_response(&html)
+    }
+
+    /// Follows a short link and returns the username of the profile or LIVE it redirects to.
+    pub async fn resolve_share_link(&self, link: &str) -> Result<String, LibError> {
+        let mut request_builder = self.factory.request();
+        request_builder.with_url(link);
+        let response = self
+            .send_with_retry(
+                &mut request_builder,
+                self.settings.rate_limits.tiktok.as_ref(),
+                &self.settings.retry.user_data,
+            )
+            .await?;
+
+        match parse_live_url(response.url().as_str())? {
+            LiveUrl::User(user_name) => Ok(user_name),
+            LiveUrl::ShareLink(_) => Err(LibError::InvalidUrl(format!(
+                "'{}' does not redirect to a profile or LIVE",
+                link
+            ))),
+        }
+    }
+
+    /// Runs `settings.room_id_resolvers` in order, returning the first room found or the first
+    /// resolver's error.
+    pub async fn resolve_live_user_data(
+        &self,
+        request: LiveUserDataRequest,
+    ) -> Result<LiveUserDataResponse, LibError> {
+        let mut first_error = None;
+        for resolver in &self.settings.room_id_resolvers {
+            let request = LiveUserDataRequest {
+                user_name: request.user_name.clone(),
+            };
+            let result = match resolver {
+                RoomIdResolver::UserRoomApi => self.fetch_live_user_data(request).await,
+                RoomIdResolver::LivePage => self.fetch_live_page_data(request).await,
+            };
+            match result {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    log::warn!("Room id resolver {:?} failed: {}", resolver, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.unwrap_or(LibError::RoomIDFieldMissing))
+    }
+
     pub async fn fetch_live_data(
         &self,
         request: LiveDataRequest,
     ) -> Result<LiveDataResponse, LibError> {
         let url = format!("{}{}", TIKTOK_URL_WEBCAST, "room/info");
-        let option = self
-            .factory
-            .request()
+        let mut request_builder = self.factory.request();
+        request_builder
             .with_url(url.as_str())
-            .with_param("room_id", &request.room_id)
-            .as_json()
-            .await;
+            .with_param("room_id", &request.room_id);
 
-        let json = option.ok_or(LibError::HttpRequestFailed)?;
+        let response = self
+            .send_with_retry(
+                &mut request_builder,
+                self.settings.rate_limits.webcast.as_ref(),
+                &self.settings.retry.room_info,
+            )
+            .await?;
+        let json = response.text().await.map_err(|_| LibError::HttpRequestFailed)?;
         map_live_data_response(json)
     }
 
@@ -67,65 +180,179 @@ impl TikTokLiveHttpClient {
         &self,
         request: LiveConnectionDataRequest,
     ) -> Result<LiveConnectionDataResponse, LibError> {
+        let mut proto_result = self.fetch_messages(&request.room_id, "", "").await?;
+        for message in &mut proto_result.messages {
+            message.is_history = true;
+        }
+
+        // ws_url არის საჭირო ველი, unless TikTok wants us to poll
+        let web_socket_url = url::Url::parse(&proto_result.ws_url).ok();
+        // The im/fetch response has put ttwid and friends into the jar.
+        let web_socket_cookies = match &web_socket_url {
+            Some(url) => self
+                .factory
+                .cookie_jar
+                .cookie_header(url)
+                .ok_or(LibError::HeaderNotReceived)?,
+            None => String::new(),
+        };
+        Ok(LiveConnectionDataResponse {
+            web_socket_timeout: self.settings.http_data.time_out,
+            web_socket_cookies,
+            web_socket_url,
+            proxy: self.factory.settings.http_data.proxy.clone(),
+            room_id: request.room_id,
+            cursor: proto_result.cursor,
+            internal_ext: proto_result.internal_ext,
+            fetch_interval: Duration::from_millis(proto_result.fetch_interval.max(0) as u64),
+            history: proto_result.messages,
+        })
+    }
+
+    /// Signs and calls `webcast/im/fetch`, which returns the messages after `cursor`.
+    pub async fn fetch_messages(
+        &self,
+        room_id: &str,
+        cursor: &str,
+        internal_ext: &str,
+    ) -> Result<ProtoMessageFetchResult, LibError> {
         // Preparing URL to sign
         let url_to_sign = self
             .factory
             .request()
             .with_url(&format!("{}{}", TIKTOK_URL_WEBCAST, "im/fetch"))
-            .with_param("room_id", &request.room_id)
+            .with_param("room_id", room_id)
+            .with_param("cursor", cursor)
+            .with_param("internal_ext", internal_ext)
             .as_url();
 
         // Signing URL
-        let option = self
-            .factory
-            .request()
-            .with_url(TIKTOK_SIGN_API)
-            .with_param("client", "ttlive-rust")
-            .with_param("uuc", "1")
-            .with_param("url", &url_to_sign)
-            .with_param("apiKey", &self.settings.sign_api_key)
-            .as_json()
-            .await;
-
-        let json = option.ok_or(LibError::UrlSigningFailed)?;
-        let sign_server_response = map_sign_server_response(json);
+        let sign_server_response = self.sign_url(&url_to_sign).await?;
 
         // Getting credentials for connection to websocket
-        let response = self
-            .factory
-            .request()
+        let mut request_builder = self.factory.request();
+        request_builder
             .with_reset()
             .with_time_out(self.settings.http_data.time_out)
-            .with_url(&sign_server_response.signed_url)
-            .build_get_request()
-            .send()
-            .await
-            .map_err(|_| LibError::HttpRequestFailed)?;
-
-        let optional_header = response.headers().get("set-cookie");
-        let header_value = optional_header
-            .ok_or(LibError::HeaderNotReceived)?
-            .to_str()
-            .map_err(|_| LibError::HeaderNotReceived)?
-            .to_string();
+            .with_url(&sign_server_response.signed_url);
+        if !sign_server_response.user_agent.is_empty() {
+            request_builder.with_header("User-Agent", &sign_server_response.user_agent);
+        }
+        for (name, value) in &sign_server_response.headers {
+            request_builder.with_header(name, value);
+        }
+        // Signer cookies go into the jar, so the websocket handshake sends them as well.
+        self.factory.cookie_jar.extend(&sign_server_response.cookies);
+        let response = self
+            .send_with_retry(
+                &mut request_builder,
+                self.settings.rate_limits.webcast.as_ref(),
+                &self.settings.retry.im_fetch,
+            )
+            .await?;
 
         let protocol_buffer_message = response.bytes().await.map_err(|_| LibError::BytesParseError)?;
 
         // --- Refactoring Step: Use prost::Message::decode ---
         // Use ProtoMessageFetchResult instead of WebcastResponse
-        let proto_result = ProtoMessageFetchResult::decode(protocol_buffer_message.as_ref())
-            .map_err(|e| {
-                eprintln!("Failed to decode ProtoMessageFetchResult: {:?}", e);
-                LibError::BytesParseError
-            })?;
-
-        // ws_url არის საჭირო ველი
-        let web_socket_url = proto_result.ws_url.clone();
-        let url = url::Url::parse(&web_socket_url).map_err(|_| LibError::InvalidHost)?;
-        Ok(LiveConnectionDataResponse {
-            web_socket_timeout: self.settings.http_data.time_out,
-            web_socket_cookies: header_value,
-            web_socket_url: url,
+        ProtoMessageFetchResult::decode(protocol_buffer_message.as_ref()).map_err(|e| {
+            eprintln!("Failed to decode ProtoMessageFetchResult: {:?}", e);
+            LibError::BytesParseError
         })
     }
-}
\ No newline at end of file
+
+    /// Sends the request until it succeeds or `policy` gives up, waiting for `limiter` first.
+    async fn send_with_retry(
+        &self,
+        request_builder: &mut HttpRequestBuilder,
+        limiter: Option<&RateLimiter>,
+        policy: &RetryPolicy,
+    ) -> Result<reqwest::Response, LibError> {
+        let mut attempt = 1;
+        loop {
+            if let Some(limiter) = limiter {
+                limiter.acquire().await;
+            }
+            let (retry_after, error) = match request_builder.build_get_request().send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) if policy.retry_statuses.contains(&response.status().as_u16()) => {
+                    (retry_after(&response), LibError::HttpRequestFailed)
+                }
+                Err(e) if e.is_timeout() || e.is_connect() => {
+                    let error = self.network_error(&e);
+                    if !policy.retry_network_errors {
+                        return Err(error);
+                    }
+                    (None, error)
+                }
+                _ => return Err(LibError::HttpRequestFailed),
+            };
+            let Some(delay) = policy.delay(attempt, retry_after) else {
+                return Err(error);
+            };
+            log::warn!(
+                "Request failed, retrying in {}ms ({}/{})",
+                delay.as_millis(),
+                attempt,
+                policy.max_attempts.saturating_sub(1)
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Connection errors while a proxy is set are reported as proxy failures, so `connect`
+    /// can move on to the next proxy.
+    fn network_error(&self, error: &reqwest::Error) -> LibError {
+        if self.settings.http_data.proxy.is_some() {
+            LibError::ProxyConnectFailed(error.to_string())
+        } else {
+            LibError::HttpRequestFailed
+        }
+    }
+
+    /// Signs `url`, retrying rate limited and failed sign requests as the sign policy allows.
+    async fn sign_url(&self, url: &str) -> Result<SignServerResponse, LibError> {
+        let policy = &self.settings.retry.sign;
+        let mut attempt = 1;
+        loop {
+            if let Some(limiter) = &self.settings.rate_limits.sign {
+                limiter.acquire().await;
+            }
+            let error = match self.signer.sign_url(url).await {
+                Err(e @ LibError::SignRateLimited { .. })
+                    if policy.retry_statuses.contains(&429) =>
+                {
+                    e
+                }
+                Err(e @ LibError::SignServerError(_)) if policy.retry_network_errors => e,
+                result => return result,
+            };
+            let retry_after = match &error {
+                LibError::SignRateLimited { retry_after } => *retry_after,
+                _ => None,
+            };
+            let Some(delay) = policy.delay(attempt, retry_after) else {
+                return Err(error);
+            };
+            log::warn!(
+                "Sign request failed, retrying in {}ms ({}/{})",
+                delay.as_millis(),
+                attempt,
+                policy.max_attempts.saturating_sub(1)
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Reads a `Retry-After` header given in seconds.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get("retry-after")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
@@ -1,15 +1,24 @@
 // live_client_mapper.rs
 use crate::core::live_client::TikTokLiveClient;
 // Import our new, custom event enum.
+use crate::core::live_client_barrage::TikTokLiveBarrage;
 use crate::core::live_client_events::TikTokLiveEvent;
+use crate::core::live_client_text::CommonOnlyMessage;
+use crate::core::live_client_treasure::TikTokLiveTreasureChest;
 // Import the prost Message trait, which gives us the `.decode()` method.
 use prost::Message;
 
 // Import all the necessary generated structs directly from our new module.
 // We will need these to decode the binary payload of each message.
 use crate::generated::{
-    BaseProtoMessage, WebcastChatMessage, WebcastGiftMessage, WebcastLikeMessage,
-    WebcastMemberMessage, ProtoMessageFetchResult,
+    BaseProtoMessage, WebcastBarrageMessage, WebcastCaptionMessage, WebcastChatMessage,
+    WebcastEmoteChatMessage, WebcastEnvelopeMessage, WebcastGiftMessage, WebcastGoalUpdateMessage,
+    WebcastHourlyRankMessage, WebcastImDeleteMessage, WebcastInRoomBannerMessage,
+    WebcastLikeMessage, WebcastLinkLayerMessage, WebcastLinkMessage, WebcastLinkMicArmies,
+    WebcastLinkMicBattle, WebcastLinkMicBattlePunishFinish, WebcastLiveIntroMessage,
+    WebcastMemberMessage, WebcastOecLiveShoppingMessage, WebcastPollMessage,
+    WebcastQuestionNewMessage, WebcastRankTextMessage, WebcastRankUpdateMessage, WebcastRoomMessage,
+    WebcastRoomPinMessage, ProtoMessageFetchResult,
 };
 
 #[derive(Clone)]
@@ -22,8 +31,13 @@ impl TikTokLiveMessageMapper {
         proto_result: ProtoMessageFetchResult,
         client: &TikTokLiveClient,
     ) {
+        self.handle_messages(&proto_result.messages, client);
+    }
+
+    /// Handles a batch of messages, events carry each message's `is_history` flag.
+    pub fn handle_messages(&self, messages: &[BaseProtoMessage], client: &TikTokLiveClient) {
         // Iterate through each individual message container within the response.
-        for message in &proto_result.messages {
+        for message in messages {
             // Pass each message to our new handler function.
             self.handle_single_message(message, client);
         }
@@ -33,6 +47,7 @@ impl TikTokLiveMessageMapper {
     fn handle_single_message(&self, message: &BaseProtoMessage, client: &TikTokLiveClient) {
         // The `r#type` field tells us what kind of event this is (e.g., "WebcastChatMessage").
         let msg_type = &message.r#type;
+        let publish = |event| client.publish_message_event(event, message.is_history);
 
         // We use a `match` statement to handle only the events we care about.
         // For each event type, we attempt to decode the binary `payload` into the
@@ -40,26 +55,175 @@ impl TikTokLiveMessageMapper {
         match msg_type.as_str() {
             "WebcastChatMessage" => {
                 if let Ok(msg) = WebcastChatMessage::decode(message.payload.as_ref()) {
-                    client.publish_event(TikTokLiveEvent::OnChatMessage(msg));
+                    client.chat_history.push(&msg);
+                    publish(TikTokLiveEvent::OnChatMessage(msg));
+                }
+            }
+            "WebcastEmoteChatMessage" => {
+                if let Ok(msg) = WebcastEmoteChatMessage::decode(message.payload.as_ref()) {
+                    publish(TikTokLiveEvent::OnEmoteChat(msg));
                 }
             }
             "WebcastGiftMessage" => {
                 if let Ok(msg) = WebcastGiftMessage::decode(message.payload.as_ref()) {
-                    client.publish_event(TikTokLiveEvent::OnGiftMessage(msg));
+                    publish(TikTokLiveEvent::OnGiftMessage(msg));
                 }
             }
             "WebcastLikeMessage" => {
                 if let Ok(msg) = WebcastLikeMessage::decode(message.payload.as_ref()) {
-                    client.publish_event(TikTokLiveEvent::OnLikeMessage(msg));
+                    publish(TikTokLiveEvent::OnLikeMessage(msg));
                 }
             }
             "WebcastMemberMessage" => {
                 if let Ok(msg) = WebcastMemberMessage::decode(message.payload.as_ref()) {
-                    client.publish_event(TikTokLiveEvent::OnMemberMessage(msg));
+                    publish(TikTokLiveEvent::OnMemberMessage(msg));
+                }
+            }
+            "WebcastLinkMicBattle" => {
+                if let Ok(msg) = WebcastLinkMicBattle::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.battle_tracker.handle_battle(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastLinkMicArmies" => {
+                if let Ok(msg) = WebcastLinkMicArmies::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.battle_tracker.handle_armies(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastLinkMicBattlePunishFinish" => {
+                if let Ok(msg) = WebcastLinkMicBattlePunishFinish::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.battle_tracker.handle_punish_finish(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastPollMessage" => {
+                if let Ok(msg) = WebcastPollMessage::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.poll_tracker.handle_poll(&msg) {
+                        publish(event);
+                    }
                 }
             }
-            // We ignore all other message types for now.
-            _ => {}
+            "WebcastGoalUpdateMessage" => {
+                if let Ok(msg) = WebcastGoalUpdateMessage::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.goal_tracker.handle_goal_update(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastEnvelopeMessage" => {
+                if let Ok(msg) = WebcastEnvelopeMessage::decode(message.payload.as_ref()) {
+                    if let Some(chest) = TikTokLiveTreasureChest::from_message(&msg) {
+                        publish(TikTokLiveEvent::OnTreasureChest(chest));
+                    }
+                }
+            }
+            "WebcastCaptionMessage" => {
+                if let Ok(msg) = WebcastCaptionMessage::decode(message.payload.as_ref()) {
+                    for event in client.caption_assembler.handle_caption(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastQuestionNewMessage" => {
+                if let Ok(msg) = WebcastQuestionNewMessage::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.question_queue.handle_question(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastHourlyRankMessage" => {
+                if let Ok(msg) = WebcastHourlyRankMessage::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.rank_tracker.handle_hourly_rank(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastRankUpdateMessage" => {
+                if let Ok(msg) = WebcastRankUpdateMessage::decode(message.payload.as_ref()) {
+                    for event in client.rank_tracker.handle_rank_update(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastRankTextMessage" => {
+                if let Ok(msg) = WebcastRankTextMessage::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.rank_tracker.handle_rank_text(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastImDeleteMessage" => {
+                if let Ok(msg) = WebcastImDeleteMessage::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.chat_history.handle_delete(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastRoomPinMessage" => {
+                if let Ok(msg) = WebcastRoomPinMessage::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.pin_tracker.handle_pin(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastOecLiveShoppingMessage" => {
+                if let Ok(msg) = WebcastOecLiveShoppingMessage::decode(message.payload.as_ref()) {
+                    if let Some(event) = client.shopping_tracker.handle_shopping(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastLinkMessage" => {
+                if let Ok(msg) = WebcastLinkMessage::decode(message.payload.as_ref()) {
+                    for event in client.guest_tracker.handle_link(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastLinkLayerMessage" => {
+                if let Ok(msg) = WebcastLinkLayerMessage::decode(message.payload.as_ref()) {
+                    for event in client.guest_tracker.handle_link_layer(&msg) {
+                        publish(event);
+                    }
+                }
+            }
+            "WebcastLiveIntroMessage" => {
+                if let Ok(msg) = WebcastLiveIntroMessage::decode(message.payload.as_ref()) {
+                    client.set_intro(&msg.description);
+                    publish(TikTokLiveEvent::OnLiveIntro(msg));
+                }
+            }
+            "WebcastInRoomBannerMessage" => {
+                if let Ok(msg) = WebcastInRoomBannerMessage::decode(message.payload.as_ref()) {
+                    publish(TikTokLiveEvent::OnRoomBanner(msg));
+                }
+            }
+            "WebcastRoomMessage" => {
+                if let Ok(msg) = WebcastRoomMessage::decode(message.payload.as_ref()) {
+                    publish(TikTokLiveEvent::OnRoomNotice(msg));
+                }
+            }
+            "WebcastBarrageMessage" => {
+                if let Ok(msg) = WebcastBarrageMessage::decode(message.payload.as_ref()) {
+                    let barrage = TikTokLiveBarrage::from_message(&msg);
+                    publish(TikTokLiveEvent::OnBarrage(barrage));
+                }
+            }
+            // Everything else is passed through with its common data, so `display_text()` works.
+            _ => {
+                let common = CommonOnlyMessage::decode(message.payload.as_ref())
+                    .ok()
+                    .and_then(|msg| msg.common);
+                publish(TikTokLiveEvent::OnOtherMessage {
+                    method: message.r#type.clone(),
+                    common,
+                    payload: message.payload.clone(),
+                });
+            }
         }
     }
 }
\ No newline at end of file
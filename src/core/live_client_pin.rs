@@ -0,0 +1,97 @@
+// live_client_pin.rs
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::webcast_room_pin_message::WebcastMsg;
+use crate::generated::{User, WebcastRoomPinMessage};
+
+/// The pinned chat, social, gift, member or like message.
+pub type PinnedContent = WebcastMsg;
+
+/// `WebcastRoomPinMessage::action` value that removes a pin.
+pub const PIN_ACTION_UNPIN: i32 = 2;
+
+/// The item currently pinned in the room.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLivePin {
+    pub pin_id: i64,
+    pub operator: Option<User>,
+    pub pinned: PinnedContent,
+    pub pinned_at: SystemTime,
+    /// `None` when the pin stays until it is removed.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl TikTokLivePin {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= SystemTime::now())
+    }
+}
+
+/// Tracks pins and unpins from `WebcastRoomPinMessage`.
+#[derive(Default)]
+pub struct TikTokLivePinTracker {
+    pin: Mutex<Option<TikTokLivePin>>,
+}
+
+impl TikTokLivePinTracker {
+    /// Returns the currently pinned item, if its display time has not run out.
+    pub fn get_pin(&self) -> Option<TikTokLivePin> {
+        self.pin
+            .lock()
+            .unwrap()
+            .clone()
+            .filter(|pin| !pin.is_expired())
+    }
+
+    pub(crate) fn handle_pin(&self, message: &WebcastRoomPinMessage) -> Option<TikTokLiveEvent> {
+        let mut current = self.pin.lock().unwrap();
+
+        if message.action == PIN_ACTION_UNPIN {
+            if current
+                .as_ref()
+                .is_some_and(|pin| pin.pin_id == message.pin_id)
+            {
+                *current = None;
+            }
+            return Some(TikTokLiveEvent::OnMessageUnpinned {
+                pin_id: message.pin_id,
+                operator: message.operator.clone(),
+            });
+        }
+
+        let pinned = message.webcast_msg.clone()?;
+        let pinned_at = timestamp_to_time(message.pin_time);
+        let expires_at = (message.display_duration > 0)
+            .then(|| pinned_at + Duration::from_secs(message.display_duration as u64));
+        *current = Some(TikTokLivePin {
+            pin_id: message.pin_id,
+            operator: message.operator.clone(),
+            pinned: pinned.clone(),
+            pinned_at,
+            expires_at,
+        });
+
+        Some(TikTokLiveEvent::OnMessagePinned {
+            pin_id: message.pin_id,
+            operator: message.operator.clone(),
+            pinned,
+            expires_at,
+        })
+    }
+}
+
+/// `pin_time` is sent in milliseconds, older payloads use seconds.
+fn timestamp_to_time(timestamp: i64) -> SystemTime {
+    if timestamp <= 0 {
+        return SystemTime::now();
+    }
+    let duration = if timestamp > 1_000_000_000_000 {
+        Duration::from_millis(timestamp as u64)
+    } else {
+        Duration::from_secs(timestamp as u64)
+    };
+    UNIX_EPOCH + duration
+}
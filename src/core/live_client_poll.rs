@@ -0,0 +1,143 @@
+// live_client_poll.rs
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::{PollOptionInfo, WebcastPollMessage};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollOption {
+    pub option_id: i32,
+    pub text: String,
+    pub votes: i32,
+}
+
+/// Current state of the room poll, kept so overlays can render it at any time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLivePoll {
+    pub poll_id: i64,
+    pub title: String,
+    pub options: Vec<PollOption>,
+    pub duration: Duration,
+    pub time_remaining: Duration,
+    pub gift_name: Option<String>,
+    pub ended: bool,
+}
+
+impl TikTokLivePoll {
+    /// The option with the most votes, `None` when nobody voted or the top options are tied.
+    pub fn winner(&self) -> Option<PollOption> {
+        let top = self.options.iter().max_by_key(|option| option.votes)?;
+        let tied = self.options.iter().filter(|option| option.votes == top.votes).count() > 1;
+        if top.votes == 0 || tied {
+            return None;
+        }
+        Some(top.clone())
+    }
+
+    /// Votes per `option_id`.
+    pub fn totals(&self) -> HashMap<i32, i32> {
+        self.options
+            .iter()
+            .map(|option| (option.option_id, option.votes))
+            .collect()
+    }
+
+    fn apply_options(&mut self, options: &[PollOptionInfo]) {
+        for info in options {
+            match self.options.iter_mut().find(|o| o.option_id == info.option_idx) {
+                Some(option) => {
+                    option.votes = info.votes;
+                    if !info.display_content.is_empty() {
+                        option.text = info.display_content.clone();
+                    }
+                }
+                None => self.options.push(PollOption {
+                    option_id: info.option_idx,
+                    text: info.display_content.clone(),
+                    votes: info.votes,
+                }),
+            }
+        }
+        self.options.sort_by_key(|option| option.option_id);
+    }
+}
+
+/// Turns `WebcastPollMessage` start, update and end contents into poll events.
+#[derive(Default)]
+pub struct TikTokLivePollTracker {
+    poll: Mutex<Option<TikTokLivePoll>>,
+}
+
+impl TikTokLivePollTracker {
+    pub fn get_poll(&self) -> Option<TikTokLivePoll> {
+        self.poll.lock().unwrap().clone()
+    }
+
+    pub(crate) fn handle_poll(&self, message: &WebcastPollMessage) -> Option<TikTokLiveEvent> {
+        let mut current = self.poll.lock().unwrap();
+        if current.as_ref().map(|p| p.poll_id) != Some(message.poll_id) {
+            *current = Some(TikTokLivePoll {
+                poll_id: message.poll_id,
+                title: String::new(),
+                options: vec![],
+                duration: Duration::ZERO,
+                time_remaining: Duration::ZERO,
+                gift_name: None,
+                ended: false,
+            });
+        }
+        let poll = current.as_mut()?;
+
+        if let Some(info) = &message.poll_basic_info {
+            if !info.title.is_empty() {
+                poll.title = info.title.clone();
+            }
+            if info.poll_duration > 0 {
+                poll.duration = Duration::from_secs(info.poll_duration as u64);
+            }
+            poll.time_remaining = Duration::from_secs(info.time_remain.max(0) as u64);
+            if let Some(gift) = &info.gift {
+                poll.gift_name = Some(gift.gift_name.clone());
+            }
+        }
+
+        if let Some(end) = &message.end_content {
+            poll.apply_options(&end.option_list);
+            poll.time_remaining = Duration::ZERO;
+            poll.ended = true;
+            return Some(TikTokLiveEvent::OnPollEnded {
+                poll_id: poll.poll_id,
+                winner: poll.winner(),
+                totals: poll.totals(),
+            });
+        }
+
+        if let Some(start) = &message.start_content {
+            if !start.title.is_empty() {
+                poll.title = start.title.clone();
+            }
+            if poll.duration.is_zero() && start.end_time > start.start_time {
+                poll.duration = Duration::from_secs((start.end_time - start.start_time) as u64);
+            }
+            poll.apply_options(&start.option_list);
+            return Some(TikTokLiveEvent::OnPollStarted {
+                poll_id: poll.poll_id,
+                title: poll.title.clone(),
+                options: poll.options.clone(),
+                duration: poll.duration,
+            });
+        }
+
+        if let Some(update) = &message.update_content {
+            poll.apply_options(&update.option_list);
+            return Some(TikTokLiveEvent::OnPollVotesUpdated {
+                poll_id: poll.poll_id,
+                votes: poll.totals(),
+            });
+        }
+
+        None
+    }
+}
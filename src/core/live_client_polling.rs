@@ -0,0 +1,118 @@
+// live_client_polling.rs
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info, warn};
+
+use crate::core::live_client::TikTokLiveClient;
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::core::live_client_mapper::TikTokLiveMessageMapper;
+use crate::data::live_common::ConnectionState::{CONNECTED, DISCONNECTED};
+use crate::errors::LibError;
+use crate::http::http_data::LiveConnectionDataResponse;
+
+/// Shortest wait between two polls, whatever `fetch_interval` TikTok sends.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Polling stops after this many failed fetches in a row.
+const MAX_POLL_FAILURES: u32 = 5;
+
+/// Receives messages by polling `webcast/im/fetch` over HTTP, for networks that block the
+/// websocket.
+pub struct TikTokLivePollingClient {
+    pub(crate) message_mapper: TikTokLiveMessageMapper,
+    pub(crate) running: Arc<AtomicBool>,
+}
+
+impl TikTokLivePollingClient {
+    pub fn new(message_mapper: TikTokLiveMessageMapper) -> Self {
+        TikTokLivePollingClient {
+            message_mapper,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub async fn start(
+        &self,
+        response: &LiveConnectionDataResponse,
+        client: Arc<TikTokLiveClient>,
+    ) -> Result<(), LibError> {
+        client.set_connection_state(CONNECTED);
+        client.publish_event(TikTokLiveEvent::OnConnected);
+        // Recent chats, viewers and pins from before we connected.
+        self.message_mapper
+            .handle_messages(&response.history, client.as_ref());
+
+        self.run(
+            response.room_id.clone(),
+            response.cursor.clone(),
+            response.internal_ext.clone(),
+            response.fetch_interval,
+            client,
+        );
+        Ok(())
+    }
+
+    /// Polls from `cursor` on without announcing a new connection, used when an `Auto`
+    /// websocket drops after it was connected.
+    pub(crate) fn run(
+        &self,
+        room_id: String,
+        mut cursor: String,
+        mut internal_ext: String,
+        fetch_interval: Duration,
+        client: Arc<TikTokLiveClient>,
+    ) {
+        let running = self.running.clone();
+        running.store(true, Ordering::SeqCst);
+
+        let message_mapper = self.message_mapper.clone();
+        let mut fetch_interval = fetch_interval.max(MIN_POLL_INTERVAL);
+
+        tokio::spawn(async move {
+            info!("Polling started");
+            let mut failures = 0;
+            while running.load(Ordering::SeqCst) {
+                tokio::time::sleep(fetch_interval).await;
+                if !running.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let proto_result = match client
+                    .http_client
+                    .fetch_messages(&room_id, &cursor, &internal_ext)
+                    .await
+                {
+                    Ok(result) => result,
+                    Err(e) => {
+                        failures += 1;
+                        if failures >= MAX_POLL_FAILURES {
+                            error!("Polling failed {} times in a row: {}", failures, e);
+                            break;
+                        }
+                        warn!("Polling failed ({}/{}): {}", failures, MAX_POLL_FAILURES, e);
+                        continue;
+                    }
+                };
+                failures = 0;
+
+                cursor = proto_result.cursor.clone();
+                internal_ext = proto_result.internal_ext.clone();
+                if proto_result.fetch_interval > 0 {
+                    fetch_interval = Duration::from_millis(proto_result.fetch_interval as u64)
+                        .max(MIN_POLL_INTERVAL);
+                }
+                message_mapper.handle_webcast_response(proto_result, client.as_ref());
+            }
+            running.store(false, Ordering::SeqCst);
+            client.set_connection_state(DISCONNECTED);
+            client.flush_captions();
+            client.publish_event(TikTokLiveEvent::OnDisconnected);
+            info!("Polling stopped.");
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,76 @@
+// live_client_question.rs
+use std::sync::Mutex;
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::{User, WebcastQuestionNewMessage};
+
+/// A question submitted through the LIVE Q&A panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLiveQuestion {
+    pub question_id: i64,
+    pub text: String,
+    pub user: Option<User>,
+    pub create_time: i64,
+    pub answered: bool,
+}
+
+/// Keeps the room's Q&A questions in the order they were asked.
+#[derive(Default)]
+pub struct TikTokLiveQuestionQueue {
+    questions: Mutex<Vec<TikTokLiveQuestion>>,
+}
+
+impl TikTokLiveQuestionQueue {
+    pub fn get_questions(&self) -> Vec<TikTokLiveQuestion> {
+        self.questions.lock().unwrap().clone()
+    }
+
+    pub fn get_pending_questions(&self) -> Vec<TikTokLiveQuestion> {
+        self.questions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|question| !question.answered)
+            .cloned()
+            .collect()
+    }
+
+    /// Marks a question as answered, returns `false` if the question is unknown.
+    pub fn mark_answered(&self, question_id: i64) -> bool {
+        let mut questions = self.questions.lock().unwrap();
+        match questions.iter_mut().find(|q| q.question_id == question_id) {
+            Some(question) => {
+                question.answered = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn handle_question(
+        &self,
+        message: &WebcastQuestionNewMessage,
+    ) -> Option<TikTokLiveEvent> {
+        let details = message.details.as_ref()?;
+        let question = TikTokLiveQuestion {
+            question_id: details.question_id,
+            text: details.question_text.clone(),
+            user: details.user.clone(),
+            create_time: details.create_time,
+            answered: details.answer_status != 0,
+        };
+
+        let mut questions = self.questions.lock().unwrap();
+        match questions
+            .iter_mut()
+            .find(|q| q.question_id == question.question_id)
+        {
+            Some(existing) => *existing = question.clone(),
+            None => {
+                questions.push(question.clone());
+                questions.sort_by_key(|q| q.create_time);
+            }
+        }
+        Some(TikTokLiveEvent::OnQuestion(question))
+    }
+}
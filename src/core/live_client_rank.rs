@@ -0,0 +1,146 @@
+// live_client_rank.rs
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::{
+    ProfitRankType, WebcastHourlyRankMessage, WebcastRankTextMessage, WebcastRankUpdateMessage,
+};
+
+/// List name used for `WebcastRankTextMessage`, which only covers the room's viewer ranking.
+pub const RANK_LIST_ONLINE_AUDIENCE: &str = "online_audience";
+
+/// The host's current place on one TikTok leaderboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLiveRank {
+    /// Leaderboard name, e.g. `hourly_rank` or `weekly_rank`.
+    pub list: String,
+    /// 1-based position, `None` when the host is not on the list.
+    pub position: Option<i64>,
+    pub text: String,
+}
+
+/// Normalizes hourly rank, rank update and rank text messages into `OnRankUpdate` events.
+#[derive(Default)]
+pub struct TikTokLiveRankTracker {
+    ranks: Mutex<HashMap<String, TikTokLiveRank>>,
+}
+
+impl TikTokLiveRankTracker {
+    pub fn get_rank(&self, list: &str) -> Option<TikTokLiveRank> {
+        self.ranks.lock().unwrap().get(list).cloned()
+    }
+
+    pub fn get_ranks(&self) -> Vec<TikTokLiveRank> {
+        let mut ranks: Vec<TikTokLiveRank> = self.ranks.lock().unwrap().values().cloned().collect();
+        ranks.sort_by(|a, b| a.list.cmp(&b.list));
+        ranks
+    }
+
+    pub(crate) fn handle_hourly_rank(
+        &self,
+        message: &WebcastHourlyRankMessage,
+    ) -> Option<TikTokLiveEvent> {
+        let ranking = message.data.as_ref()?.rankings.as_ref()?;
+        let list = if ranking.r#type.is_empty() {
+            rank_list_name(ProfitRankType::TypeHourlyRank as i64)
+        } else {
+            ranking.r#type.clone()
+        };
+        // The hourly ranking only carries a label such as "No. 3", so the position is read from it.
+        let position = ranking
+            .label
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|part| !part.is_empty())
+            .and_then(|digits| digits.parse().ok());
+        Some(self.update(list, position, ranking.label.clone()))
+    }
+
+    pub(crate) fn handle_rank_update(
+        &self,
+        message: &WebcastRankUpdateMessage,
+    ) -> Vec<TikTokLiveEvent> {
+        message
+            .updates_list
+            .iter()
+            .map(|update| {
+                let position = Some(update.owner_rank).filter(|rank| *rank > 0);
+                let text = update
+                    .default_content
+                    .as_ref()
+                    .map(|content| content.render())
+                    .unwrap_or_default();
+                self.update(rank_list_name(update.rank_type), position, text)
+            })
+            .collect()
+    }
+
+    pub(crate) fn handle_rank_text(
+        &self,
+        message: &WebcastRankTextMessage,
+    ) -> Option<TikTokLiveEvent> {
+        let text = message
+            .self_get_badge_msg
+            .as_ref()
+            .or(message.other_get_badge_msg.as_ref())
+            .map(|content| content.render())
+            .unwrap_or_default();
+        let old_position = index_to_position(message.owner_idx_before_update);
+        let new_position = index_to_position(message.owner_idx_after_update);
+
+        let list = RANK_LIST_ONLINE_AUDIENCE.to_string();
+        self.ranks.lock().unwrap().insert(
+            list.clone(),
+            TikTokLiveRank {
+                list: list.clone(),
+                position: new_position,
+                text: text.clone(),
+            },
+        );
+        Some(TikTokLiveEvent::OnRankUpdate {
+            list,
+            old_position,
+            new_position,
+            text,
+        })
+    }
+
+    fn update(&self, list: String, position: Option<i64>, text: String) -> TikTokLiveEvent {
+        let mut ranks = self.ranks.lock().unwrap();
+        let old_position = ranks.get(&list).and_then(|rank| rank.position);
+        ranks.insert(
+            list.clone(),
+            TikTokLiveRank {
+                list: list.clone(),
+                position,
+                text: text.clone(),
+            },
+        );
+        TikTokLiveEvent::OnRankUpdate {
+            list,
+            old_position,
+            new_position: position,
+            text,
+        }
+    }
+}
+
+/// Turns a `ProfitRankType` into a list name such as `hourly_rank`.
+fn rank_list_name(rank_type: i64) -> String {
+    i32::try_from(rank_type)
+        .ok()
+        .and_then(|value| ProfitRankType::try_from(value).ok())
+        .map(|rank_type| {
+            let name = rank_type.as_str_name();
+            name.strip_prefix("PROFIT_RANK_TYPE_")
+                .unwrap_or(name)
+                .trim_start_matches("TYPE_")
+                .to_lowercase()
+        })
+        .unwrap_or_else(|| format!("rank_{}", rank_type))
+}
+
+/// Owner indexes are 0-based and negative when the host is not on the list.
+fn index_to_position(index: i64) -> Option<i64> {
+    (index >= 0).then_some(index + 1)
+}
@@ -0,0 +1,99 @@
+// live_client_shopping.rs
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::core::live_client_events::TikTokLiveEvent;
+use crate::generated::WebcastOecLiveShoppingMessage;
+
+/// A product showcased during a LIVE shopping stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLiveProduct {
+    pub title: String,
+    /// Price as displayed, e.g. `$55.99`.
+    pub price: String,
+    pub image_url: String,
+    pub shop_url: String,
+    pub shop_name: String,
+    pub shown_at: SystemTime,
+}
+
+impl TikTokLiveProduct {
+    /// Whether both are the same listing, TikTok re-sends the featured product repeatedly.
+    fn is_same_listing(&self, other: &TikTokLiveProduct) -> bool {
+        self.title == other.title && self.price == other.price && self.shop_url == other.shop_url
+    }
+}
+
+/// Keeps the currently featured product and a bounded history of the products shown.
+pub struct TikTokLiveShoppingTracker {
+    capacity: usize,
+    featured: Mutex<Option<TikTokLiveProduct>>,
+    history: Mutex<VecDeque<TikTokLiveProduct>>,
+}
+
+impl TikTokLiveShoppingTracker {
+    pub fn new(capacity: usize) -> Self {
+        TikTokLiveShoppingTracker {
+            capacity,
+            featured: Mutex::new(None),
+            history: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn get_featured_product(&self) -> Option<TikTokLiveProduct> {
+        self.featured.lock().unwrap().clone()
+    }
+
+    /// Returns the showcased products in the order they were shown.
+    pub fn get_product_history(&self) -> Vec<TikTokLiveProduct> {
+        self.history.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub(crate) fn handle_shopping(
+        &self,
+        message: &WebcastOecLiveShoppingMessage,
+    ) -> Option<TikTokLiveEvent> {
+        let data = message.shop_data.as_ref()?;
+        let create_time = message
+            .common
+            .as_ref()
+            .map(|common| common.create_time)
+            .unwrap_or_default();
+        let shown_at = if create_time > 0 {
+            UNIX_EPOCH + Duration::from_millis(create_time as u64)
+        } else {
+            SystemTime::now()
+        };
+        let product = TikTokLiveProduct {
+            title: data.title.clone(),
+            price: data.price_string.clone(),
+            image_url: data.image_url.clone(),
+            shop_url: if data.shop_url.is_empty() {
+                data.shop_url2.clone()
+            } else {
+                data.shop_url.clone()
+            },
+            shop_name: data.shop_name.clone(),
+            shown_at,
+        };
+
+        let mut featured = self.featured.lock().unwrap();
+        // A repeat keeps the time the product was first shown and is not published again.
+        if featured
+            .as_ref()
+            .is_some_and(|featured| featured.is_same_listing(&product))
+        {
+            return None;
+        }
+        *featured = Some(product.clone());
+        if self.capacity > 0 {
+            let mut history = self.history.lock().unwrap();
+            if history.len() == self.capacity {
+                history.pop_front();
+            }
+            history.push_back(product.clone());
+        }
+        Some(TikTokLiveEvent::OnShoppingProduct(product))
+    }
+}
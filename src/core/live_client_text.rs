@@ -0,0 +1,135 @@
+// live_client_text.rs
+use crate::generated::text::text_piece::TextPieceType;
+use crate::generated::text::{TextFormat, TextPiece};
+use crate::generated::{CommonMessageData, Text};
+
+/// Every webcast message carries its `CommonMessageData` at tag 1, which lets us read the
+/// `display_text` of message types we do not decode specifically.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct CommonOnlyMessage {
+    #[prost(message, optional, tag = "1")]
+    pub common: Option<CommonMessageData>,
+}
+
+/// Renders `common.display_text`, `None` when it is missing or renders to nothing.
+pub fn render_common(common: Option<&CommonMessageData>) -> Option<String> {
+    let text = common?.display_text.as_ref()?.render();
+    Some(text).filter(|text| !text.trim().is_empty())
+}
+
+/// A run of rendered text with the style TikTok asks it to be shown in.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextSegment {
+    pub text: String,
+    /// Color as sent by TikTok, usually `#RRGGBB` or `#AARRGGBB`.
+    pub color: Option<String>,
+    pub bold: bool,
+    pub italic: bool,
+    pub weight: i32,
+    pub font_size: i32,
+}
+
+impl TextSegment {
+    fn new(text: String, format: Option<&TextFormat>) -> Self {
+        match format {
+            Some(format) => TextSegment {
+                text,
+                color: Some(format.color.clone()).filter(|color| !color.is_empty()),
+                bold: format.bold,
+                italic: format.italic,
+                weight: format.weight,
+                font_size: format.font_size,
+            },
+            None => TextSegment {
+                text,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Text {
+    /// Resolves `default_pattern` (e.g. `"{0:user} joined"`) with its pieces into plain text.
+    pub fn render(&self) -> String {
+        self.render_segments()
+            .into_iter()
+            .map(|segment| segment.text)
+            .collect()
+    }
+
+    /// Like [`Text::render`], but keeps the `TextFormat` of every piece.
+    pub fn render_segments(&self) -> Vec<TextSegment> {
+        let default_format = self.default_format.as_ref();
+        let pattern = self.default_pattern.as_str();
+        let mut segments = vec![];
+        let mut literal = String::new();
+        let mut rest = pattern;
+
+        while let Some(open) = rest.find('{') {
+            let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+                break;
+            };
+            let placeholder = &rest[open + 1..close];
+            let index = placeholder
+                .split(':')
+                .next()
+                .and_then(|index| index.trim().parse::<usize>().ok());
+            let Some(index) = index else {
+                // Not a placeholder, keep the braces as they are.
+                literal.push_str(&rest[..=open]);
+                rest = &rest[open + 1..];
+                continue;
+            };
+
+            literal.push_str(&rest[..open]);
+            if !literal.is_empty() {
+                segments.push(TextSegment::new(std::mem::take(&mut literal), default_format));
+            }
+            if let Some(piece) = self.pieces_list.get(index) {
+                let format = piece.format.as_ref().or(default_format);
+                segments.push(TextSegment::new(render_piece(piece), format));
+            }
+            rest = &rest[close + 1..];
+        }
+
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            segments.push(TextSegment::new(literal, default_format));
+        }
+        segments
+    }
+}
+
+fn render_piece(piece: &TextPiece) -> String {
+    match &piece.text_piece_type {
+        Some(TextPieceType::UserValue(value)) => {
+            let name = value
+                .user
+                .as_ref()
+                .map(|user| {
+                    if user.nickname.is_empty() {
+                        user.unique_id.clone()
+                    } else {
+                        user.nickname.clone()
+                    }
+                })
+                .unwrap_or_default();
+            if value.with_colon {
+                format!("{}:", name)
+            } else {
+                name
+            }
+        }
+        Some(TextPieceType::GiftValue(value)) => value
+            .name_ref
+            .as_ref()
+            .map(|name| name.default_pattern.clone())
+            .unwrap_or_default(),
+        None => piece
+            .pattern_ref_value
+            .as_ref()
+            .map(|pattern| pattern.default_pattern.clone())
+            .filter(|pattern| !pattern.is_empty())
+            .unwrap_or_else(|| piece.string_value.clone()),
+    }
+}
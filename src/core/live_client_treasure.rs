@@ -0,0 +1,54 @@
+// live_client_treasure.rs
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::generated::{EnvelopeBusinessType, EnvelopeFollowShowStatus, WebcastEnvelopeMessage};
+
+/// A treasure chest (envelope) dropped into the room, mapped from `WebcastEnvelopeMessage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TikTokLiveTreasureChest {
+    pub envelope_id: String,
+    pub sender_id: String,
+    pub sender_name: String,
+    pub diamond_count: i32,
+    /// How many viewers can claim a share of the chest.
+    pub people_count: i32,
+    pub business_type: EnvelopeBusinessType,
+    /// Whether viewers have to follow the host to open the chest.
+    pub follow_required: bool,
+    /// Unix timestamp in seconds at which the chest can be opened.
+    pub unpack_at: i64,
+}
+
+impl TikTokLiveTreasureChest {
+    pub fn from_message(message: &WebcastEnvelopeMessage) -> Option<Self> {
+        let info = message.envelope_info.as_ref()?;
+        Some(TikTokLiveTreasureChest {
+            envelope_id: info.envelope_id.clone(),
+            sender_id: info.send_user_id.clone(),
+            sender_name: info.send_user_name.clone(),
+            diamond_count: info.diamond_count,
+            people_count: info.people_count,
+            business_type: EnvelopeBusinessType::try_from(info.business_type)
+                .unwrap_or(EnvelopeBusinessType::BusinessTypeUnknown),
+            follow_required: info.follow_show_status
+                == EnvelopeFollowShowStatus::EnvelopeFollowShow as i32,
+            unpack_at: info.unpack_at as i64,
+        })
+    }
+
+    /// The moment the chest opens.
+    pub fn opens_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.unpack_at.max(0) as u64)
+    }
+
+    /// Time left until the chest opens, zero if it can already be opened.
+    pub fn time_until_open(&self) -> Duration {
+        self.opens_at()
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.time_until_open().is_zero()
+    }
+}
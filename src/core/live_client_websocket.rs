@@ -1,21 +1,26 @@
 use futures_util::{SinkExt, StreamExt};
-use log::info;
+use log::{info, warn};
 use prost::Message;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::time::{interval, timeout, Duration};
 use tokio_tungstenite::tungstenite::handshake::client::Request;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message as WsMessage};
+use tokio_tungstenite::{client_async_tls, tungstenite::protocol::Message as WsMessage};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 
 use crate::core::live_client::TikTokLiveClient;
 use crate::core::live_client_mapper::TikTokLiveMessageMapper;
-use crate::data::live_common::ConnectionState::{self, CONNECTED, DISCONNECTED};
+use crate::data::live_common::ConnectionState::{CONNECTED, DISCONNECTED};
+use crate::data::live_common::TransportMode;
 use crate::errors::LibError;
 use crate::core::live_client_events::TikTokLiveEvent;
 use crate::http::http_data::LiveConnectionDataResponse;
+use crate::http::http_proxy::{connect_stream, ProxyStream};
 use crate::generated::{ProtoMessageFetchResult, WebcastPushFrame};
 
+pub type TikTokWebSocketStream = WebSocketStream<MaybeTlsStream<Box<dyn ProxyStream>>>;
+
 pub struct TikTokLiveWebsocketClient {
     pub(crate) message_mapper: TikTokLiveMessageMapper,
     pub(crate) running: Arc<AtomicBool>,
@@ -29,19 +34,20 @@ impl TikTokLiveWebsocketClient {
         }
     }
 
-    pub async fn start(
+    /// Opens the websocket, through `response.proxy` when one is set.
+    pub async fn connect(
         &self,
-        response: LiveConnectionDataResponse,
-        client: Arc<TikTokLiveClient>,
-    ) -> Result<(), LibError> {
-        let host = response
+        response: &LiveConnectionDataResponse,
+    ) -> Result<TikTokWebSocketStream, LibError> {
+        let web_socket_url = response
             .web_socket_url
-            .host_str()
+            .clone()
             .ok_or(LibError::InvalidHost)?;
+        let host = web_socket_url.host_str().ok_or(LibError::InvalidHost)?;
 
         let request = Request::builder()
             .method("GET")
-            .uri(response.web_socket_url.to_string())
+            .uri(web_socket_url.to_string())
             .header("Host", host)
             .header("Upgrade", "websocket")
             .header("Connection", "keep-alive")
@@ -53,19 +59,36 @@ impl TikTokLiveWebsocketClient {
             .header("Origin", "https://www.tiktok.com")
             .header("Accept-Language", "en-US,en;q=0.9")
             .header("Accept-Encoding", "gzip, deflate")
-            .header("Cookie", response.web_socket_cookies)
+            .header("Cookie", &response.web_socket_cookies)
             .header("Sec-Websocket-Version", "13")
             .body(())
             .map_err(|_| LibError::ParamsError)?;
 
-        let (ws_stream, _) = connect_async(request)
+        let port = web_socket_url
+            .port_or_known_default()
+            .ok_or(LibError::InvalidHost)?;
+        let stream = connect_stream(response.proxy.as_ref(), host, port).await?;
+        let (ws_stream, _) = client_async_tls(request, stream)
             .await
             .map_err(|_| LibError::WebSocketConnectFailed)?;
+        Ok(ws_stream)
+    }
+
+    /// Starts listening on a websocket opened by [`Self::connect`].
+    pub async fn start(
+        &self,
+        ws_stream: TikTokWebSocketStream,
+        response: &LiveConnectionDataResponse,
+        client: Arc<TikTokLiveClient>,
+    ) -> Result<(), LibError> {
         let (write, mut read) = ws_stream.split();
         let write = Arc::new(Mutex::new(write));
 
         client.set_connection_state(CONNECTED);
         client.publish_event(TikTokLiveEvent::OnConnected);
+        // Recent chats, viewers and pins from before we connected.
+        self.message_mapper
+            .handle_messages(&response.history, client.as_ref());
 
         let running = self.running.clone();
         running.store(true, Ordering::SeqCst);
@@ -74,6 +97,10 @@ impl TikTokLiveWebsocketClient {
         let client_clone = client.clone();
         let write_clone = write.clone();
         let running_clone = running.clone();
+        let room_id = response.room_id.clone();
+        let mut cursor = response.cursor.clone();
+        let mut internal_ext = response.internal_ext.clone();
+        let fetch_interval = response.fetch_interval;
 
         tokio::spawn(async move {
             info!("Websocket connected");
@@ -106,6 +133,10 @@ impl TikTokLiveWebsocketClient {
                             }
                         }
 
+                        // Where polling continues if the websocket drops.
+                        cursor = proto_result.cursor.clone();
+                        internal_ext = proto_result.internal_ext.clone();
+
                         // This was already correct in your code.
                         message_mapper.handle_webcast_response(proto_result, client_clone.as_ref());
                     }
@@ -114,8 +145,14 @@ impl TikTokLiveWebsocketClient {
                 }
             }
             // Logic to handle disconnection
-            running_clone.store(false, Ordering::SeqCst);
+            let dropped = running_clone.swap(false, Ordering::SeqCst);
+            if dropped && client_clone.settings.transport == TransportMode::Auto {
+                warn!("Websocket dropped, falling back to polling...");
+                client_clone.polling_client.run(room_id, cursor, internal_ext, fetch_interval, client_clone.clone());
+                return;
+            }
             client_clone.set_connection_state(DISCONNECTED);
+            client_clone.flush_captions();
             client_clone.publish_event(TikTokLiveEvent::OnDisconnected);
             info!("Websocket listener stopped.");
         });
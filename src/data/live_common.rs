@@ -1,8 +1,12 @@
 // live_common.rs
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+
+use crate::http::http_retry::{RateLimits, RetrySettings};
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -973,11 +977,37 @@ pub struct Demotion {
 #[derive(Clone)]
 pub struct TikTokLiveSettings {
     pub host_name: String,
+    /// Connects to this room directly, skipping the username lookup.
+    pub room_id: Option<String>,
+    /// Short link that is followed on connect to find `host_name`.
+    pub share_link: Option<String>,
     pub language: String,
     pub sign_api_key: String,
     pub reconnect_on_fail: bool,
     pub print_logs: bool,
     pub http_data: HttpData,
+    /// Tried in order until one finds the room id of `host_name`.
+    pub room_id_resolvers: Vec<RoomIdResolver>,
+    /// How events are received, see [`TransportMode`].
+    pub transport: TransportMode,
+    /// Retry policy per endpoint.
+    pub retry: RetrySettings,
+    /// Request rate limits per endpoint family, share them between clients to spread out
+    /// bulk startups.
+    pub rate_limits: RateLimits,
+    /// Proxies handed out to clients in turn, overrides `http_data.proxy` when set.
+    pub proxy_pool: Option<ProxyPool>,
+    /// Logged-in TikTok session, needed for age or region restricted streams.
+    pub session: Option<TikTokLiveSession>,
+    /// Loads cookies from and saves them to this JSON file, so they survive between runs.
+    /// Session cookies are never written to it.
+    pub cookie_jar_path: Option<PathBuf>,
+    /// Writes finished caption sentences to transcript files when set.
+    pub caption_export: Option<CaptionExport>,
+    /// Number of chat messages kept by the client, `0` disables the history.
+    pub chat_history_size: usize,
+    /// Number of showcased products kept by the client, `0` disables the history.
+    pub product_history_size: usize,
 }
 
 #[derive(Clone, Default)]
@@ -986,6 +1016,128 @@ pub struct HttpData {
     pub params: HashMap<String, String>,
     pub headers: HashMap<String, String>,
     pub cookies: HashMap<String, String>,
+    /// Proxy for HTTP requests and the websocket, `None` connects directly.
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl TikTokLiveSettings {
+    /// Names the stream in logs and file names, the room id when there is no username.
+    pub fn display_name(&self) -> &str {
+        match &self.room_id {
+            Some(room_id) if self.host_name.is_empty() => room_id,
+            _ => &self.host_name,
+        }
+    }
+}
+
+/// An `http://`, `https://`, `socks5://` or `socks5h://` proxy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProxyConfig {
+    /// Proxy URL, e.g. `socks5://127.0.0.1:1080`.
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl ProxyConfig {
+    pub fn new(url: &str) -> Self {
+        ProxyConfig {
+            url: url.to_string(),
+            username: None,
+            password: None,
+        }
+    }
+
+    pub fn with_auth(mut self, username: &str, password: &str) -> Self {
+        self.username = Some(username.to_string());
+        self.password = Some(password.to_string());
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ProxyRotation {
+    /// Every new client, and so every room, gets the next proxy.
+    #[default]
+    PerRoom,
+    /// Like `PerRoom`, and a client also moves to the next proxy when connecting fails.
+    OnFailure,
+}
+
+/// Round-robin list of proxies, clones share the rotation position.
+#[derive(Clone, Debug, Default)]
+pub struct ProxyPool {
+    pub proxies: Vec<ProxyConfig>,
+    pub rotation: ProxyRotation,
+    next: Arc<AtomicUsize>,
+}
+
+impl ProxyPool {
+    pub fn new(proxies: Vec<ProxyConfig>, rotation: ProxyRotation) -> Self {
+        ProxyPool {
+            proxies,
+            rotation,
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn next_proxy(&self) -> Option<ProxyConfig> {
+        if self.proxies.is_empty() {
+            return None;
+        }
+        let index = self.next.fetch_add(1, Ordering::SeqCst) % self.proxies.len();
+        Some(self.proxies[index].clone())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoomIdResolver {
+    /// The `api-live/user/room` JSON endpoint.
+    UserRoomApi,
+    /// The state embedded in the `tiktok.com/@{user}/live` page.
+    LivePage,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TransportMode {
+    /// Websocket, falling back to polling when it cannot connect or drops later on.
+    #[default]
+    Auto,
+    WebSocket,
+    /// Polls `webcast/im/fetch` over HTTP, every poll costs a sign request.
+    Polling,
+}
+
+/// Cookies of a logged-in TikTok account, taken from the browser.
+#[derive(Clone, Debug, Default)]
+pub struct TikTokLiveSession {
+    /// Value of the `sessionid` cookie.
+    pub session_id: String,
+    /// Value of the `tt-target-idc` cookie, the data center of the account, e.g. `useast2a`.
+    pub tt_target_idc: String,
+    pub usage: SessionUsage,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SessionUsage {
+    /// Every request is made with the session.
+    #[default]
+    Always,
+    /// Connects anonymously first, and retries with the session when the stream is age or
+    /// region restricted.
+    OnRestriction,
+}
+
+#[derive(Clone, Debug)]
+pub struct CaptionExport {
+    pub directory: PathBuf,
+    pub format: CaptionFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaptionFormat {
+    Srt,
+    WebVtt,
 }
 
 #[derive(Default)]
@@ -999,17 +1151,14 @@ pub struct TikTokLiveInfo {
     pub title: String,
     pub language: String,
     pub connection_state: Mutex<ConnectionState>,
+    /// The host's LIVE intro, updated from `WebcastLiveIntroMessage`.
+    pub intro: Mutex<String>,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Default)]
 pub enum ConnectionState {
     CONNECTING,
     CONNECTED,
+    #[default]
     DISCONNECTED,
 }
-
-impl Default for ConnectionState {
-    fn default() -> Self {
-        ConnectionState::DISCONNECTED
-    }
-}
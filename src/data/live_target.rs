@@ -0,0 +1,112 @@
+// live_target.rs
+use url::Url;
+
+use crate::errors::LibError;
+
+/// Longest username TikTok allows.
+const MAX_USER_NAME_LENGTH: usize = 24;
+
+/// What a TikTok link points to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LiveUrl {
+    /// `tiktok.com/@user` or `tiktok.com/@user/live`, with the normalized username.
+    User(String),
+    /// A `vm.tiktok.com` or `tiktok.com/t/` short link, which only resolves by following it.
+    ShareLink(String),
+}
+
+/// Trims whitespace and a leading `@`, and lowercases the username.
+pub fn clean_user_name(user_name: &str) -> String {
+    let user_name = user_name.trim();
+    user_name
+        .strip_prefix('@')
+        .unwrap_or(user_name)
+        .trim()
+        .to_lowercase()
+}
+
+/// Cleans the username like [`clean_user_name`] and checks it is a valid TikTok username.
+pub fn normalize_user_name(user_name: &str) -> Result<String, LibError> {
+    let user_name = clean_user_name(user_name);
+    let invalid = |reason: &str| LibError::InvalidUserName(format!("'{}' {}", user_name, reason));
+
+    if user_name.is_empty() {
+        return Err(LibError::InvalidUserName("username is empty".to_string()));
+    }
+    if user_name.chars().count() > MAX_USER_NAME_LENGTH {
+        return Err(invalid(&format!(
+            "is longer than {} characters",
+            MAX_USER_NAME_LENGTH
+        )));
+    }
+    if let Some(c) = user_name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '.'))
+    {
+        return Err(invalid(&format!(
+            "contains '{}', only letters, digits, '_' and '.' are allowed",
+            c
+        )));
+    }
+    if user_name.ends_with('.') {
+        return Err(invalid("ends with '.'"));
+    }
+    Ok(user_name)
+}
+
+/// Parses profile, live and short links. The scheme may be left out.
+pub fn parse_live_url(url: &str) -> Result<LiveUrl, LibError> {
+    let trimmed = url.trim();
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    };
+    let parsed = Url::parse(&with_scheme)
+        .map_err(|e| LibError::InvalidUrl(format!("'{}' is not a URL: {}", trimmed, e)))?;
+    let host = parsed.host_str().unwrap_or_default();
+    if host != "tiktok.com" && !host.ends_with(".tiktok.com") {
+        return Err(LibError::InvalidUrl(format!(
+            "'{}' is not a TikTok link",
+            trimmed
+        )));
+    }
+
+    let segments: Vec<&str> = parsed
+        .path_segments()
+        .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+        .unwrap_or_default();
+    let is_short_host = host == "vm.tiktok.com" || host == "vt.tiktok.com";
+    if is_short_host || segments.first() == Some(&"t") {
+        // The code follows the host of `vm.tiktok.com/{code}` and the `t` of `tiktok.com/t/{code}`.
+        let code_index = if is_short_host { 0 } else { 1 };
+        if segments.len() <= code_index {
+            return Err(LibError::InvalidUrl(format!(
+                "'{}' is a short link without a code",
+                trimmed
+            )));
+        }
+        return Ok(LiveUrl::ShareLink(parsed.to_string()));
+    }
+
+    match segments.first() {
+        Some(segment) if segment.starts_with('@') => {
+            Ok(LiveUrl::User(normalize_user_name(segment)?))
+        }
+        _ => Err(LibError::InvalidUrl(format!(
+            "'{}' does not point to a profile or LIVE",
+            trimmed
+        ))),
+    }
+}
+
+/// Checks that a room id is numeric.
+pub fn validate_room_id(room_id: &str) -> Result<(), LibError> {
+    if room_id.is_empty() || !room_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(LibError::InvalidRoomId(format!(
+            "'{}' must be a number",
+            room_id
+        )));
+    }
+    Ok(())
+}
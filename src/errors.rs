@@ -1,5 +1,6 @@
 // errors.rs
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum LibError {
@@ -28,13 +29,49 @@ pub enum LibError {
     AckPacketSendError,
     HttpRequestFailed,
     UrlSigningFailed,
+    /// The sign server rejected `sign_api_key`, or none was given where one is required.
+    SignApiKeyInvalid,
+    /// The sign server asked us to slow down, `retry_after` is its suggested wait.
+    SignRateLimited {
+        retry_after: Option<Duration>,
+    },
+    /// The sign server quota of this API key is used up.
+    SignQuotaExhausted,
+    /// The sign server failed or answered with something we could not read.
+    SignServerError(String),
     HeaderNotReceived,
     BytesParseError,
+    /// The session in the settings is malformed.
+    InvalidSession(String),
+    /// The stream is age restricted and needs a logged-in session.
+    AgeRestricted,
+    /// The stream is not available in this region without a logged-in session.
+    RegionRestricted,
+    /// The proxy URL could not be parsed or uses an unsupported scheme.
+    InvalidProxy(String),
+    InvalidRateLimit(String),
+    /// The proxy refused or failed the websocket tunnel.
+    ProxyConnectFailed(String),
+    /// The live page HTML has no embedded state script.
+    LivePageStateMissing,
+    InvalidUserName(String),
+    InvalidRoomId(String),
+    InvalidUrl(String),
+}
+
+impl LibError {
+    /// Whether connecting again with a logged-in session could succeed.
+    pub fn needs_session(&self) -> bool {
+        matches!(
+            self,
+            LibError::AgeRestricted | LibError::RegionRestricted | LibError::LiveStatusFieldMissing
+        )
+    }
 }
 
 impl fmt::Display for LibError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
+        match self {
             LibError::RoomIDFieldMissing => {
                 write!(f, "Room ID field is missing, contact developer")
             }
@@ -65,8 +102,35 @@ impl fmt::Display for LibError {
             LibError::AckPacketSendError => write!(f, "Unable to send ack packet"),
             LibError::HttpRequestFailed => write!(f, "HTTP request failed"),
             LibError::UrlSigningFailed => write!(f, "URL signing failed"),
+            LibError::SignApiKeyInvalid => write!(f, "Sign server API key is invalid or missing"),
+            LibError::SignRateLimited { retry_after } => match retry_after {
+                Some(retry_after) => write!(
+                    f,
+                    "Sign server rate limit reached, retry after {}s",
+                    retry_after.as_secs()
+                ),
+                None => write!(f, "Sign server rate limit reached"),
+            },
+            LibError::SignQuotaExhausted => write!(f, "Sign server quota is exhausted"),
+            LibError::SignServerError(message) => write!(f, "Sign server error: {}", message),
             LibError::HeaderNotReceived => write!(f, "Header was not received"),
             LibError::BytesParseError => write!(f, "Unable to parse bytes to Push Frame!"),
+            LibError::InvalidSession(reason) => write!(f, "Invalid session: {}", reason),
+            LibError::AgeRestricted => {
+                write!(f, "Live stream is age restricted, a session is needed")
+            }
+            LibError::RegionRestricted => {
+                write!(f, "Live stream is region restricted, a session is needed")
+            }
+            LibError::InvalidProxy(reason) => write!(f, "Invalid proxy: {}", reason),
+            LibError::InvalidRateLimit(reason) => write!(f, "Invalid rate limit: {}", reason),
+            LibError::ProxyConnectFailed(reason) => {
+                write!(f, "Failed to connect through proxy: {}", reason)
+            }
+            LibError::LivePageStateMissing => write!(f, "Live page has no embedded state"),
+            LibError::InvalidUserName(reason) => write!(f, "Invalid username: {}", reason),
+            LibError::InvalidRoomId(reason) => write!(f, "Invalid room id: {}", reason),
+            LibError::InvalidUrl(reason) => write!(f, "Invalid URL: {}", reason),
         }
     }
 }
@@ -1,5 +1,9 @@
 // http.rs
+pub mod http_cookie_jar;
 pub mod http_data;
 
 pub mod http_data_mappers;
+pub mod http_proxy;
 pub mod http_request_builder;
+pub mod http_retry;
+pub mod http_signer;
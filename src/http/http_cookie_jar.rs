@@ -0,0 +1,171 @@
+// http_cookie_jar.rs
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use log::warn;
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use url::Url;
+
+/// Cookies of a logged-in session, they are kept in memory only and never written to the file.
+const SESSION_COOKIES: [&str; 4] = ["sessionid", "sessionid_ss", "sid_tt", "tt-target-idc"];
+
+/// Cookie store shared by all requests of a client.
+///
+/// Cookies are kept by name only and are sent to TikTok hosts only, so session cookies never
+/// leak to the sign server or other third parties.
+#[derive(Debug, Default)]
+pub struct TikTokLiveCookieJar {
+    cookies: Mutex<HashMap<String, String>>,
+    /// Session cookies, sent like the others but not persisted.
+    session_cookies: Mutex<HashMap<String, String>>,
+    path: Option<PathBuf>,
+}
+
+impl TikTokLiveCookieJar {
+    /// Creates a jar that is loaded from and saved to `path` as JSON.
+    ///
+    /// A missing or unreadable file starts an empty jar.
+    pub fn with_file(path: PathBuf) -> Self {
+        let mut cookies: HashMap<String, String> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        // A session saved by an older version must not log in every request of this run.
+        cookies.retain(|name, _| !is_session_cookie(name));
+        TikTokLiveCookieJar {
+            cookies: Mutex::new(cookies),
+            session_cookies: Mutex::default(),
+            path: Some(path),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.get_all().get(name).cloned()
+    }
+
+    pub fn get_all(&self) -> HashMap<String, String> {
+        let mut cookies = self.cookies.lock().unwrap().clone();
+        cookies.extend(self.session_cookies.lock().unwrap().clone());
+        cookies
+    }
+
+    /// Stores a cookie, session cookies are kept in memory only.
+    pub fn insert(&self, name: &str, value: &str) {
+        self.extend(&HashMap::from([(name.to_string(), value.to_string())]));
+    }
+
+    pub fn extend(&self, cookies: &HashMap<String, String>) {
+        if cookies.is_empty() {
+            return;
+        }
+        let (session, other): (HashMap<_, _>, HashMap<_, _>) = cookies
+            .clone()
+            .into_iter()
+            .partition(|(name, _)| is_session_cookie(name));
+        self.session_cookies.lock().unwrap().extend(session);
+        if !other.is_empty() {
+            self.cookies.lock().unwrap().extend(other);
+            self.save();
+        }
+    }
+
+    /// Returns the `Cookie` header value for `url`, `None` for non-TikTok hosts or an empty jar.
+    pub fn cookie_header(&self, url: &Url) -> Option<String> {
+        if !is_tiktok_host(url) {
+            return None;
+        }
+        let cookies = self.get_all();
+        if cookies.is_empty() {
+            return None;
+        }
+        Some(join_cookies(&cookies))
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let json = serde_json::to_string_pretty(&*self.cookies.lock().unwrap());
+        if let Err(e) = json.map(|json| fs::write(path, json)) {
+            warn!("Failed to save cookies to {}: {}", path.display(), e);
+        }
+    }
+}
+
+impl CookieStore for TikTokLiveCookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        if !is_tiktok_host(url) {
+            return;
+        }
+        let received: Vec<(String, String)> = cookie_headers
+            .filter_map(|header| header.to_str().ok())
+            .filter_map(parse_set_cookie)
+            .collect();
+        if received.is_empty() {
+            return;
+        }
+        {
+            let mut cookies = self.cookies.lock().unwrap();
+            let mut session_cookies = self.session_cookies.lock().unwrap();
+            for (name, value) in received {
+                let cookies = if is_session_cookie(&name) {
+                    &mut *session_cookies
+                } else {
+                    &mut *cookies
+                };
+                // An empty value is how servers delete a cookie.
+                if value.is_empty() {
+                    cookies.remove(&name);
+                } else {
+                    cookies.insert(name, value);
+                }
+            }
+        }
+        self.save();
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.cookie_header(url)
+            .and_then(|header| HeaderValue::from_str(&header).ok())
+    }
+}
+
+/// Domains of TikTok web, webcast and websocket hosts.
+const TIKTOK_DOMAINS: [&str; 3] = ["tiktok.com", "tiktokv.com", "tiktokv.us"];
+
+pub fn is_tiktok_host(url: &Url) -> bool {
+    url.host_str().is_some_and(|host| {
+        TIKTOK_DOMAINS.iter().any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|sub| sub.ends_with('.'))
+        })
+    })
+}
+
+fn is_session_cookie(name: &str) -> bool {
+    SESSION_COOKIES.contains(&name)
+}
+
+pub fn join_cookies(cookies: &HashMap<String, String>) -> String {
+    cookies
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Takes `name=value` from a `Set-Cookie` header, ignoring its attributes.
+fn parse_set_cookie(header: &str) -> Option<(String, String)> {
+    let pair = header.split(';').next()?;
+    let (name, value) = pair.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some((name.to_string(), value.trim().to_string()))
+}
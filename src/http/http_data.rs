@@ -1,8 +1,12 @@
 // http_data.rs
+use std::collections::HashMap;
 use std::time::Duration;
 
 use url::Url;
 
+use crate::data::live_common::ProxyConfig;
+use crate::generated::BaseProtoMessage;
+
 pub struct LiveUserDataRequest {
     pub user_name: String,
 }
@@ -35,12 +39,28 @@ pub struct LiveConnectionDataRequest {
 pub struct LiveConnectionDataResponse {
     pub web_socket_timeout: Duration,
     pub web_socket_cookies: String,
-    pub web_socket_url: Url,
+    /// `None` when TikTok only offers polling for this room.
+    pub web_socket_url: Option<Url>,
+    /// Proxy the websocket connects through, the same one the HTTP requests used.
+    pub proxy: Option<ProxyConfig>,
+    pub room_id: String,
+    /// Where polling continues from, taken from the first `im/fetch` response.
+    pub cursor: String,
+    pub internal_ext: String,
+    /// How long TikTok wants us to wait between two polls.
+    pub fetch_interval: Duration,
+    /// Messages of the first `im/fetch` response, marked `is_history`.
+    pub history: Vec<BaseProtoMessage>,
 }
 
+#[derive(Debug, Clone, Default)]
 pub struct SignServerResponse {
     pub signed_url: String,
     pub user_agent: String,
+    /// Extra cookies the signer wants sent along with the signed request.
+    pub cookies: HashMap<String, String>,
+    /// Extra headers the signer wants sent along with the signed request.
+    pub headers: HashMap<String, String>,
 }
 
 pub enum UserStatus {
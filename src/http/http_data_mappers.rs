@@ -2,8 +2,11 @@
 use crate::errors::LibError;
 use crate::http::http_data::LiveStatus::{HostNotFound, HostOffline, HostOnline};
 use crate::http::http_data::UserStatus::{Live, LivePaused, NotFound, Offline};
-use crate::http::http_data::{LiveDataResponse, LiveUserDataResponse, SignServerResponse};
+use crate::http::http_data::{
+    LiveDataResponse, LiveUserDataResponse, SignServerResponse, UserStatus,
+};
 use serde_json::Value;
+use std::time::Duration;
 
 pub fn map_live_user_data_response(json: String) -> Result<LiveUserDataResponse, LibError> {
     let json_value: Value =
@@ -31,12 +34,7 @@ pub fn map_live_user_data_response(json: String) -> Result<LiveUserDataResponse,
         .as_i64()
         .ok_or(LibError::UserStatusFieldMissing)?;
 
-    let user_status = match status {
-        2 => Live,
-        3 => LivePaused,
-        4 => Offline,
-        _ => NotFound,
-    };
+    let user_status = map_user_status(status);
 
     let live_room = option_data["liveRoom"]
         .as_object()
@@ -53,8 +51,76 @@ pub fn map_live_user_data_response(json: String) -> Result<LiveUserDataResponse,
     })
 }
 
+fn map_user_status(status: i64) -> UserStatus {
+    match status {
+        2 => Live,
+        3 => LivePaused,
+        4 => Offline,
+        _ => NotFound,
+    }
+}
+
+/// Ids of the script tags in which the live page embeds its state as JSON.
+const LIVE_PAGE_STATE_SCRIPTS: [&str; 2] = ["SIGI_STATE", "__UNIVERSAL_DATA_FOR_REHYDRATION__"];
+
+/// Reads the room id and status from the HTML of `tiktok.com/@{user}/live`.
+pub fn map_live_page_response(html: &str) -> Result<LiveUserDataResponse, LibError> {
+    let json = LIVE_PAGE_STATE_SCRIPTS
+        .iter()
+        .find_map(|id| extract_script(html, id))
+        .ok_or(LibError::LivePageStateMissing)?;
+    let json_value: Value = serde_json::from_str(json).map_err(|_| LibError::JsonParseError)?;
+
+    let user_info =
+        find_key(&json_value, "liveRoomUserInfo").ok_or(LibError::UserDataFieldMissing)?;
+    let user = user_info["user"]
+        .as_object()
+        .ok_or(LibError::UserFieldMissing)?;
+    let room_id = user
+        .get("roomId")
+        .and_then(|v| v.as_str())
+        .filter(|room_id| !room_id.is_empty())
+        .ok_or(LibError::RoomIDFieldMissing)?;
+    let status = user
+        .get("status")
+        .and_then(|v| v.as_i64())
+        .ok_or(LibError::UserStatusFieldMissing)?;
+    let start_time = user_info["liveRoom"]["startTime"]
+        .as_i64()
+        .unwrap_or_default();
+
+    Ok(LiveUserDataResponse {
+        user_status: map_user_status(status),
+        json: json.to_string(),
+        room_id: room_id.to_string(),
+        started_at_timestamp: start_time,
+    })
+}
+
+/// Returns the content of `<script id="{id}" ...>...</script>`.
+fn extract_script<'a>(html: &'a str, id: &str) -> Option<&'a str> {
+    let tag_start = html.find(&format!("id=\"{}\"", id))?;
+    let content_start = tag_start + html[tag_start..].find('>')? + 1;
+    let content_end = content_start + html[content_start..].find("</script>")?;
+    Some(html[content_start..content_end].trim())
+}
+
+/// Depth-first search for the first object field named `key`.
+fn find_key<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => map
+            .get(key)
+            .or_else(|| map.values().find_map(|value| find_key(value, key))),
+        Value::Array(values) => values.iter().find_map(|value| find_key(value, key)),
+        _ => None,
+    }
+}
+
 pub fn map_live_data_response(json: String) -> Result<LiveDataResponse, LibError> {
     let json_value: Value = serde_json::from_str(&json).map_err(|_| LibError::JsonParseError)?;
+    if let Some(error) = map_live_restriction(&json_value) {
+        return Err(error);
+    }
 
     let data = json_value["data"]
         .as_object()
@@ -101,13 +167,116 @@ pub fn map_live_data_response(json: String) -> Result<LiveDataResponse, LibError
     })
 }
 
-pub fn map_sign_server_response(json: String) -> SignServerResponse {
-    let json_value: Value = serde_json::from_str(json.as_str()).unwrap();
-    let signed_url = json_value["signedUrl"].as_str().unwrap();
-    let user_agent = json_value["User-Agent"].as_str().unwrap();
+/// TikTok status code of age restricted rooms.
+const STATUS_CODE_AGE_RESTRICTED: i64 = 4003110;
+
+/// Whole words of room info messages that mean the viewer is too young.
+const AGE_RESTRICTION_WORDS: [&str; 3] = ["age", "adult", "adults"];
+/// Whole words of room info messages that mean the room is not available where the viewer is.
+const REGION_RESTRICTION_WORDS: [&str; 4] = ["region", "regions", "country", "countries"];
+
+/// Detects room info responses that are refused because of age or region restrictions.
+fn map_live_restriction(json_value: &Value) -> Option<LibError> {
+    if json_value["status_code"].as_i64() == Some(STATUS_CODE_AGE_RESTRICTED) {
+        return Some(LibError::AgeRestricted);
+    }
+    let message = json_value["data"]["message"]
+        .as_str()
+        .or(json_value["data"]["prompts"].as_str())?
+        .to_lowercase();
+    let has_word = |words: &[&str]| {
+        message
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| words.contains(&word))
+    };
+    if has_word(&AGE_RESTRICTION_WORDS) {
+        Some(LibError::AgeRestricted)
+    } else if has_word(&REGION_RESTRICTION_WORDS) {
+        Some(LibError::RegionRestricted)
+    } else {
+        None
+    }
+}
+
+pub fn map_sign_server_response(json: String) -> Result<SignServerResponse, LibError> {
+    let json_value: Value = serde_json::from_str(json.as_str())
+        .map_err(|_| LibError::SignServerError(format!("unreadable response: {}", json)))?;
+    let signed_url = json_value["signedUrl"]
+        .as_str()
+        .ok_or_else(|| map_sign_server_error(200, None, &json))?;
+    let user_agent = json_value["User-Agent"].as_str().unwrap_or_default();
 
-    SignServerResponse {
+    Ok(SignServerResponse {
         signed_url: signed_url.to_string(),
         user_agent: user_agent.to_string(),
+        ..Default::default()
+    })
+}
+
+/// Maps a failed sign server response to the matching `LibError`.
+///
+/// `retry_after` is the value of the `Retry-After` header, when the server sent one.
+pub fn map_sign_server_error(status: u16, retry_after: Option<Duration>, body: &str) -> LibError {
+    let json_value: Value = serde_json::from_str(body).unwrap_or_default();
+    let message = json_value["message"]
+        .as_str()
+        .or(json_value["error"].as_str())
+        .unwrap_or(body)
+        .to_string();
+    let lowercase = message.to_lowercase();
+
+    match status {
+        429 => LibError::SignRateLimited {
+            retry_after: retry_after
+                .or_else(|| json_value["retry_after"].as_u64().map(Duration::from_secs)),
+        },
+        _ if lowercase.contains("quota") => LibError::SignQuotaExhausted,
+        401 | 403 => LibError::SignApiKeyInvalid,
+        _ if lowercase.contains("api key") || lowercase.contains("apikey") => {
+            LibError::SignApiKeyInvalid
+        }
+        _ if message.is_empty() => LibError::SignServerError(format!("status {}", status)),
+        _ => LibError::SignServerError(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live_page(name: &str) -> String {
+        let path = format!(
+            "{}/tests/fixtures/live_page/{}.html",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn live_page_with_sigi_state_finds_room() {
+        let response = map_live_page_response(&live_page("sigi_state_live")).unwrap();
+        assert_eq!(response.room_id, "7312345678901234567");
+        assert!(matches!(response.user_status, Live));
+        assert_eq!(response.started_at_timestamp, 1700000000);
+    }
+
+    #[test]
+    fn live_page_with_universal_data_reports_offline() {
+        let response = map_live_page_response(&live_page("universal_data_offline")).unwrap();
+        assert_eq!(response.room_id, "7312345678901234567");
+        assert!(matches!(response.user_status, Offline));
+    }
+
+    #[test]
+    fn live_page_without_state_script_is_rejected() {
+        let result = map_live_page_response(&live_page("no_state"));
+        assert!(matches!(result, Err(LibError::LivePageStateMissing)));
+    }
+
+    #[test]
+    fn live_page_with_empty_room_id_is_rejected() {
+        let result = map_live_page_response(&live_page("universal_data_empty_room_id"));
+        assert!(matches!(result, Err(LibError::RoomIDFieldMissing)));
     }
 }
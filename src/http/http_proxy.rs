@@ -0,0 +1,166 @@
+// http_proxy.rs
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::Proxy;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_native_tls::native_tls;
+use tokio_socks::tcp::Socks5Stream;
+use url::Url;
+
+use crate::data::live_common::ProxyConfig;
+use crate::errors::LibError;
+
+/// Longest proxy `CONNECT` response we are willing to read.
+const MAX_CONNECT_RESPONSE: usize = 8 * 1024;
+
+/// A TCP stream that may be tunnelled through a proxy.
+pub trait ProxyStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> ProxyStream for T {}
+
+/// Returns the proxy URL with the credentials filled in.
+pub fn proxy_url(proxy: &ProxyConfig) -> Result<Url, LibError> {
+    let mut url = Url::parse(&proxy.url).map_err(|e| LibError::InvalidProxy(e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(LibError::InvalidProxy(format!(
+            "unsupported scheme {}",
+            url.scheme()
+        )));
+    }
+    if url.host_str().is_none() {
+        return Err(LibError::InvalidProxy("missing host".to_string()));
+    }
+    if let Some(username) = &proxy.username {
+        url.set_username(username)
+            .map_err(|_| LibError::InvalidProxy("cannot set username".to_string()))?;
+    }
+    if let Some(password) = &proxy.password {
+        url.set_password(Some(password))
+            .map_err(|_| LibError::InvalidProxy("cannot set password".to_string()))?;
+    }
+    Ok(url)
+}
+
+/// Maps the proxy to a `reqwest` proxy for all HTTP requests.
+pub fn to_reqwest_proxy(proxy: &ProxyConfig) -> Result<Proxy, LibError> {
+    let url = proxy_url(proxy)?;
+    Proxy::all(url.as_str()).map_err(|e| LibError::InvalidProxy(e.to_string()))
+}
+
+/// Opens a TCP stream to `host:port`, through `proxy` when one is given.
+pub async fn connect_stream(
+    proxy: Option<&ProxyConfig>,
+    host: &str,
+    port: u16,
+) -> Result<Box<dyn ProxyStream>, LibError> {
+    let Some(proxy) = proxy else {
+        let stream = TcpStream::connect((host, port))
+            .await
+            .map_err(|_| LibError::WebSocketConnectFailed)?;
+        return Ok(Box::new(stream));
+    };
+
+    let url = proxy_url(proxy)?;
+    let proxy_host = url.host_str().unwrap_or_default().to_string();
+    let proxy_port = url.port_or_known_default().unwrap_or(1080);
+    let username = urlencoding::decode(url.username())
+        .map(|username| username.into_owned())
+        .unwrap_or_default();
+    let password = url
+        .password()
+        .and_then(|password| urlencoding::decode(password).ok())
+        .map(|password| password.into_owned());
+    let failed = |e: &dyn std::fmt::Display| LibError::ProxyConnectFailed(e.to_string());
+
+    match url.scheme() {
+        "socks5" | "socks5h" => {
+            let proxy_address = (proxy_host.as_str(), proxy_port);
+            let stream = match password {
+                Some(password) if !username.is_empty() => {
+                    Socks5Stream::connect_with_password(
+                        proxy_address,
+                        (host, port),
+                        &username,
+                        &password,
+                    )
+                    .await
+                }
+                _ => Socks5Stream::connect(proxy_address, (host, port)).await,
+            }
+            .map_err(|e| failed(&e))?;
+            Ok(Box::new(stream.into_inner()))
+        }
+        "https" => {
+            let stream = TcpStream::connect((proxy_host.as_str(), proxy_port))
+                .await
+                .map_err(|e| failed(&e))?;
+            let connector = native_tls::TlsConnector::new().map_err(|e| failed(&e))?;
+            let mut stream = tokio_native_tls::TlsConnector::from(connector)
+                .connect(&proxy_host, stream)
+                .await
+                .map_err(|e| failed(&e))?;
+            http_connect(&mut stream, host, port, &username, password.as_deref()).await?;
+            Ok(Box::new(stream))
+        }
+        _ => {
+            let mut stream = TcpStream::connect((proxy_host.as_str(), proxy_port))
+                .await
+                .map_err(|e| failed(&e))?;
+            http_connect(&mut stream, host, port, &username, password.as_deref()).await?;
+            Ok(Box::new(stream))
+        }
+    }
+}
+
+/// Opens an HTTP `CONNECT` tunnel to `host:port` on an already connected proxy stream.
+async fn http_connect<S: ProxyStream>(
+    stream: &mut S,
+    host: &str,
+    port: u16,
+    username: &str,
+    password: Option<&str>,
+) -> Result<(), LibError> {
+    let mut request = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\nProxy-Connection: Keep-Alive\r\n"
+    );
+    if !username.is_empty() {
+        let credentials = format!("{}:{}", username, password.unwrap_or_default());
+        request.push_str(&format!(
+            "Proxy-Authorization: Basic {}\r\n",
+            STANDARD.encode(credentials)
+        ));
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| LibError::ProxyConnectFailed(e.to_string()))?;
+
+    // Read one byte at a time, so nothing after the response headers is taken from the tunnel.
+    let mut response = vec![];
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        let read = stream
+            .read(&mut byte)
+            .await
+            .map_err(|e| LibError::ProxyConnectFailed(e.to_string()))?;
+        if read == 0 || response.len() >= MAX_CONNECT_RESPONSE {
+            return Err(LibError::ProxyConnectFailed(
+                "proxy closed the connection".to_string(),
+            ));
+        }
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(LibError::ProxyConnectFailed(format!(
+            "proxy answered {}",
+            status_line
+        )));
+    }
+    Ok(())
+}
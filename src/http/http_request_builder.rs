@@ -1,29 +1,75 @@
 // http_request_builder.rs
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::Bytes;
+use log::warn;
 use reqwest::{Client, RequestBuilder};
 use urlencoding::encode;
 
-use crate::data::live_common::{HttpData, TikTokLiveSettings};
+use crate::data::live_common::{HttpData, ProxyConfig, TikTokLiveSettings};
+use crate::http::http_cookie_jar::{is_tiktok_host, join_cookies, TikTokLiveCookieJar};
+use crate::http::http_proxy::to_reqwest_proxy;
 
+/// Creates requests that share one `reqwest::Client`, and with it one connection pool and
+/// one cookie jar.
 pub struct HttpRequestFactory {
     pub(crate) settings: TikTokLiveSettings,
+    pub(crate) client: Client,
+    pub(crate) cookie_jar: Arc<TikTokLiveCookieJar>,
 }
 
 impl HttpRequestFactory {
+    pub fn new(settings: TikTokLiveSettings) -> Self {
+        let cookie_jar = match &settings.cookie_jar_path {
+            Some(path) => TikTokLiveCookieJar::with_file(path.clone()),
+            None => TikTokLiveCookieJar::default(),
+        };
+        cookie_jar.extend(&settings.http_data.cookies);
+        let cookie_jar = Arc::new(cookie_jar);
+        let client = create_client(cookie_jar.clone(), settings.http_data.proxy.as_ref());
+
+        HttpRequestFactory {
+            settings,
+            client,
+            cookie_jar,
+        }
+    }
+
+    /// Rebuilds the shared client for another proxy, the cookie jar is kept.
+    pub fn set_proxy(&mut self, proxy: Option<ProxyConfig>) {
+        self.client = create_client(self.cookie_jar.clone(), proxy.as_ref());
+        self.settings.http_data.proxy = proxy;
+    }
+
     pub fn request(&self) -> HttpRequestBuilder {
         HttpRequestBuilder {
             url: "".to_string(),
             http_data: self.settings.http_data.clone(),
+            client: self.client.clone(),
+            cookie_jar: self.cookie_jar.clone(),
         }
     }
 }
 
+fn create_client(cookie_jar: Arc<TikTokLiveCookieJar>, proxy: Option<&ProxyConfig>) -> Client {
+    let mut builder = Client::builder().cookie_provider(cookie_jar);
+    if let Some(proxy) = proxy {
+        match to_reqwest_proxy(proxy) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            // `TikTokLiveClient::connect` rejects invalid proxies before sending any request.
+            Err(e) => warn!("Ignoring proxy {}: {}", proxy.url, e),
+        }
+    }
+    builder.build().unwrap()
+}
+
 pub struct HttpRequestBuilder {
     url: String,
     http_data: HttpData,
+    client: Client,
+    cookie_jar: Arc<TikTokLiveCookieJar>,
 }
 
 impl HttpRequestBuilder {
@@ -71,28 +117,49 @@ impl HttpRequestBuilder {
     }
 
     pub fn build_client(&mut self) -> Client {
-        let client = Client::builder()
-            .timeout(self.http_data.time_out)
-            .build()
-            .unwrap();
-
-        client
+        self.client.clone()
     }
     pub fn build_get_request(&mut self) -> RequestBuilder {
         let client = self.build_client();
         let url = self.as_url();
+        let cookie_header = self.cookie_header(&url);
         let mut res = client.get(url);
+        if !self.http_data.time_out.is_zero() {
+            res = res.timeout(self.http_data.time_out);
+        }
         for header in self.http_data.headers.clone() {
             res = res.header(header.0, header.1);
         }
+        if let Some(cookie_header) = cookie_header {
+            res = res.header("Cookie", cookie_header);
+        }
         res
     }
 
+    /// Merges the request cookies over the jar. `None` leaves the jar to the client, and a
+    /// `Cookie` header set by hand always wins.
+    fn cookie_header(&self, url: &str) -> Option<String> {
+        if self.http_data.cookies.is_empty()
+            || self
+                .http_data
+                .headers
+                .keys()
+                .any(|name| name.eq_ignore_ascii_case("cookie"))
+        {
+            return None;
+        }
+        url::Url::parse(url).ok().filter(is_tiktok_host)?;
+        let mut cookies = self.cookie_jar.get_all();
+        cookies.extend(self.http_data.cookies.clone());
+        Some(join_cookies(&cookies))
+    }
+
     pub async fn as_json(&mut self) -> Option<String> {
-        let result = self.build_get_request().send().await.unwrap();
+        // A dead proxy fails here, so it must not panic.
+        let result = self.build_get_request().send().await.ok()?;
 
         if result.status().is_success() {
-            let json_res = result.text().await.unwrap();
+            let json_res = result.text().await.ok()?;
             Some(json_res)
         } else {
             None
@@ -100,10 +167,10 @@ impl HttpRequestBuilder {
     }
 
     pub async fn as_bytes(&mut self) -> Option<Bytes> {
-        let result = self.build_get_request().send().await.unwrap();
+        let result = self.build_get_request().send().await.ok()?;
 
         if result.status().is_success() {
-            let bytes = result.bytes().await.unwrap();
+            let bytes = result.bytes().await.ok()?;
             Some(bytes)
         } else {
             None
@@ -111,7 +178,7 @@ impl HttpRequestBuilder {
     }
 
     pub fn as_url(&mut self) -> String {
-        if self.http_data.params.len() == 0 {
+        if self.http_data.params.is_empty() {
             return self.url.to_string();
         }
 
@@ -0,0 +1,160 @@
+// http_retry.rs
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::errors::LibError;
+
+/// When and how often a failed request is sent again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts including the first one, `1` disables retries.
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for every further retry.
+    pub backoff: Duration,
+    /// Upper bound of the exponential wait.
+    pub max_backoff: Duration,
+    /// Upper bound of a wait the server asks for with `Retry-After`, longer requests wait
+    /// this long.
+    pub max_retry_after: Duration,
+    /// HTTP statuses worth retrying, `429` also covers a rate limited sign server.
+    pub retry_statuses: Vec<u16>,
+    /// Retry timeouts, connection errors and sign server failures.
+    pub retry_network_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+            max_retry_after: Duration::from_secs(60),
+            retry_statuses: vec![429, 500, 502, 503, 504],
+            retry_network_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Wait before retry number `retry` (starting at 1), `None` when no retry is left.
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if retry >= self.max_attempts {
+            return None;
+        }
+        match retry_after {
+            Some(retry_after) => Some(retry_after.min(self.max_retry_after)),
+            None => {
+                let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+                Some(self.backoff.saturating_mul(factor).min(self.max_backoff))
+            }
+        }
+    }
+}
+
+/// Retry policy of every endpoint the client calls.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetrySettings {
+    /// `api-live/user/room`, the room ID lookup.
+    pub user_data: RetryPolicy,
+    /// `webcast/room/info`.
+    pub room_info: RetryPolicy,
+    /// `webcast/im/fetch`, the websocket credentials.
+    pub im_fetch: RetryPolicy,
+    /// The URL signer.
+    pub sign: RetryPolicy,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        RetrySettings {
+            user_data: RetryPolicy::default(),
+            room_info: RetryPolicy::default(),
+            im_fetch: RetryPolicy::default(),
+            sign: RetryPolicy {
+                max_attempts: 4,
+                backoff: Duration::from_secs(1),
+                max_backoff: Duration::from_secs(30),
+                max_retry_after: Duration::from_secs(120),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Slowest refill rate, one request per day, keeps every wait representable as a `Duration`.
+const MIN_RATE: f64 = 1.0 / 86_400.0;
+
+/// Token bucket limiting how fast requests are sent. Clones share the bucket, so one limiter
+/// can be put into the settings of many clients.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    per_second: f64,
+    bucket: Arc<Mutex<(f64, Instant)>>,
+}
+
+impl RateLimiter {
+    /// Allows bursts of `capacity` requests, refilled at `per_second` requests per second.
+    ///
+    /// Fails when `capacity` is zero or `per_second` is not a finite rate of at least one
+    /// request per day.
+    pub fn new(capacity: u32, per_second: f64) -> Result<Self, LibError> {
+        if capacity == 0 {
+            return Err(LibError::InvalidRateLimit(
+                "capacity must be at least 1".to_string(),
+            ));
+        }
+        if !per_second.is_finite() || per_second < MIN_RATE {
+            return Err(LibError::InvalidRateLimit(format!(
+                "{} requests per second is below one request per day",
+                per_second
+            )));
+        }
+        let capacity = capacity as f64;
+        Ok(RateLimiter {
+            capacity,
+            per_second,
+            bucket: Arc::new(Mutex::new((capacity, Instant::now()))),
+        })
+    }
+
+    /// Waits until a request may be sent.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                let (tokens, last_refill) = &mut *bucket;
+                let now = Instant::now();
+                let refill = now.duration_since(*last_refill).as_secs_f64() * self.per_second;
+                *tokens = (*tokens + refill).min(self.capacity);
+                *last_refill = now;
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - *tokens) / self.per_second)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Rate limiters per endpoint family, `None` does not limit that family.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimits {
+    /// `www.tiktok.com` requests.
+    pub tiktok: Option<RateLimiter>,
+    /// `webcast.tiktok.com` requests.
+    pub webcast: Option<RateLimiter>,
+    /// URL signing.
+    pub sign: Option<RateLimiter>,
+}
@@ -0,0 +1,113 @@
+// http_signer.rs
+use std::sync::RwLock;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::core::live_client_http::TIKTOK_SIGN_API;
+use crate::data::live_common::{ProxyConfig, SessionUsage, TikTokLiveSession, TikTokLiveSettings};
+use crate::errors::LibError;
+use crate::http::http_data::SignServerResponse;
+use crate::http::http_data_mappers::{map_sign_server_error, map_sign_server_response};
+use crate::http::http_request_builder::HttpRequestFactory;
+
+/// Signs the `webcast/im/fetch` URL before the client requests its websocket credentials.
+///
+/// Set a custom implementation with `TikTokLiveBuilder::with_signer`, the default is
+/// [`EulerUrlSigner`].
+#[async_trait]
+pub trait UrlSigner: Send + Sync {
+    async fn sign_url(&self, url: &str) -> Result<SignServerResponse, LibError>;
+
+    /// Called when the client moves to another proxy, so a signer that shares the client's
+    /// proxy can follow it. Does nothing by default.
+    fn set_proxy(&self, _proxy: Option<ProxyConfig>) {}
+
+    /// Called when the client starts using its session, so a signer can sign with it where
+    /// the sign server supports that. Does nothing by default.
+    fn set_session(&self, _session: Option<TikTokLiveSession>) {}
+}
+
+/// Signs URLs with the Euler Stream sign server, using `sign_api_key` from the settings.
+pub struct EulerUrlSigner {
+    api_key: String,
+    /// Set with `SessionUsage::Always`, or once the client retries with its session, the
+    /// anonymous first try must not sign with it.
+    session: RwLock<Option<TikTokLiveSession>>,
+    factory: RwLock<HttpRequestFactory>,
+}
+
+impl EulerUrlSigner {
+    pub fn new(settings: &TikTokLiveSettings) -> Self {
+        // The sign server never gets TikTok cookies, so it must not touch the cookie file either.
+        let mut settings = settings.clone();
+        settings.cookie_jar_path = None;
+        EulerUrlSigner {
+            api_key: settings.sign_api_key.clone(),
+            session: RwLock::new(
+                settings
+                    .session
+                    .clone()
+                    .filter(|session| session.usage == SessionUsage::Always),
+            ),
+            factory: RwLock::new(HttpRequestFactory::new(settings)),
+        }
+    }
+}
+
+#[async_trait]
+impl UrlSigner for EulerUrlSigner {
+    async fn sign_url(&self, url: &str) -> Result<SignServerResponse, LibError> {
+        let (mut request, proxied) = {
+            let factory = self.factory.read().unwrap();
+            (
+                factory.request(),
+                factory.settings.http_data.proxy.is_some(),
+            )
+        };
+        request
+            .with_url(TIKTOK_SIGN_API)
+            .with_param("client", "ttlive-rust")
+            .with_param("uuc", "1")
+            .with_param("url", url)
+            .with_param("apiKey", &self.api_key);
+        if let Some(session) = self.session.read().unwrap().as_ref() {
+            request
+                .with_param("sessionId", &session.session_id)
+                .with_param("ttTargetIdc", &session.tt_target_idc);
+        }
+        let response = request.build_get_request().send().await.map_err(|e| {
+            // A dead proxy has to surface as such, so `connect` moves on to the next one.
+            if proxied && (e.is_connect() || e.is_timeout()) {
+                LibError::ProxyConnectFailed(e.to_string())
+            } else {
+                LibError::SignServerError(e.to_string())
+            }
+        })?;
+
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let body = response
+            .text()
+            .await
+            .map_err(|e| LibError::SignServerError(e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(map_sign_server_error(status.as_u16(), retry_after, &body));
+        }
+        map_sign_server_response(body)
+    }
+
+    fn set_proxy(&self, proxy: Option<ProxyConfig>) {
+        self.factory.write().unwrap().set_proxy(proxy);
+    }
+
+    fn set_session(&self, session: Option<TikTokLiveSession>) {
+        *self.session.write().unwrap() = session;
+    }
+}
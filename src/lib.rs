@@ -1,6 +1,7 @@
 
 // Declare a 'generated' module.
 pub mod generated {
+    #![allow(clippy::large_enum_variant)]
     // === THIS IS THE FINAL FIX ===
     // We are now including the exact file that prost-build creates.
     // The name `tik_tok.rs` is derived from the proto package name with underscores.
@@ -23,4 +24,16 @@ impl TikTokLive {
     pub fn new_client(user_name: &str) -> core::live_client_builder::TikTokLiveBuilder {
         core::live_client_builder::TikTokLiveBuilder::new(user_name)
     }
+
+    /// Returns a builder for a client that connects to `room_id` without looking up a user.
+    pub fn from_room_id(room_id: &str) -> core::live_client_builder::TikTokLiveBuilder {
+        core::live_client_builder::TikTokLiveBuilder::from_room_id(room_id)
+    }
+
+    /// Returns a builder for the user of a profile, LIVE or `vm.tiktok.com` link.
+    pub fn from_url(
+        url: &str,
+    ) -> Result<core::live_client_builder::TikTokLiveBuilder, errors::LibError> {
+        core::live_client_builder::TikTokLiveBuilder::from_url(url)
+    }
 }
\ No newline at end of file
@@ -6,7 +6,9 @@ use std::time::Duration; // Importing Duration for timeout settings
 use tiktoklive::{
     // Importing necessary modules and structs from tiktoklive crate
     core::live_client::TikTokLiveClient,
-    data::live_common::{ClientData, StreamData, TikTokLiveSettings},
+    data::live_common::{
+        ClientData, SessionUsage, StreamData, TikTokLiveSession, TikTokLiveSettings,
+    },
     errors::LibError,
     core::live_client_events::TikTokLiveEvent,
     TikTokLive,
@@ -26,17 +28,12 @@ async fn main() {
         if let Err(e) = client.connect().await {
             match e {
                 // Match on the error type
-                LibError::LiveStatusFieldMissing => {
-                    // Specific error case
+                e if e.needs_session() => {
+                    // Age or region restricted stream, connect() already retried with SESSION_ID if set
                     warn!(
-                        "Failed to get live status (probably needs authenticated client): {}",
+                        "Failed to get live status (set SESSION_ID to connect with a session): {}",
                         e
                     );
-                    let auth_client = create_client_with_cookies(user_name); // Create an authenticated client
-                    if let Err(e) = auth_client.connect().await {
-                        // Attempt to connect the authenticated client
-                        error!("Error connecting to TikTok Live after retry: {}", e);
-                    }
                 }
                 LibError::HeaderNotReceived => {
                     error!("Error connecting to TikTok Live: {}", e);
@@ -115,22 +112,23 @@ fn init_logger(default_level: &str) {
         .init(); // Initialize the logger
 }
 
+// PLACEHOLDER: paste the value of your `sessionid` cookie here, leave empty to stay anonymous
+const SESSION_ID: &str = "";
+// PLACEHOLDER: paste the value of your `tt-target-idc` cookie here
+const TT_TARGET_IDC: &str = "";
+
 // Function to configure the TikTok live settings
 fn configure(settings: &mut TikTokLiveSettings) {
     settings.http_data.time_out = Duration::from_secs(12); // Set HTTP timeout to 12 seconds
     settings.sign_api_key = "".to_string(); // Provide your own api key here
-}
-
-// Function to configure the TikTok live settings with cookies for authentication
-fn configure_with_cookies(settings: &mut TikTokLiveSettings) {
-    settings.http_data.time_out = Duration::from_secs(12); // Set HTTP timeout to 12 seconds
-    settings.sign_api_key = "".to_string(); // Provide your own api key here
-    let contents = ""; // Placeholder for cookies
-    settings
-        .http_data
-        .headers
-        .insert("Cookie".to_string(), contents.to_string());
-    // Insert cookies into HTTP headers
+    if !SESSION_ID.is_empty() {
+        // Logged-in session, only used when anonymous access is age or region restricted
+        settings.session = Some(TikTokLiveSession {
+            session_id: SESSION_ID.to_string(),
+            tt_target_idc: TT_TARGET_IDC.to_string(),
+            usage: SessionUsage::OnRestriction,
+        });
+    }
 }
 
 // Function to create a TikTok live client for the given username
@@ -140,11 +138,3 @@ fn create_client(user_name: &str) -> TikTokLiveClient {
         .on_event(handle_event) // Set the event handler
         .build() // Build the client
 }
-
-// Function to create a TikTok live client with cookies for the given username
-fn create_client_with_cookies(user_name: &str) -> TikTokLiveClient {
-    TikTokLive::new_client(user_name) // Create a new client
-        .configure(configure_with_cookies) // Configure the client with cookies
-        .on_event(handle_event) // Set the event handler
-        .build() // Build the client
-}